@@ -0,0 +1,124 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+
+/// Overrides where `verify` reads its answers from.
+pub const ANSWERS_VAR: &str = "AOC2021_ANSWERS";
+
+/// `$AOC2021_ANSWERS` if set, otherwise the crate's own `answers.toml`.
+pub fn default_path() -> PathBuf {
+    match std::env::var_os(ANSWERS_VAR) {
+        Some(path) => PathBuf::from(path),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("answers.toml"),
+    }
+}
+
+/// Expected answers per day and part, written in a small subset of TOML:
+///
+/// ```toml
+/// [day01]
+/// part1 = 1709
+/// part2 = "1761"
+/// ```
+pub struct Answers(HashMap<(u32, u32), String>);
+
+impl Answers {
+    pub fn new(input: &str) -> Result<Answers> {
+        let mut answers = HashMap::new();
+        let mut day = None;
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |reason: &str| anyhow!("Line {}: {} in {:?}", i + 1, reason, line);
+
+            if let Some(section) = line.strip_prefix('[') {
+                let section = section.strip_suffix(']').ok_or(error("Unclosed section"))?;
+                let number = section
+                    .strip_prefix("day")
+                    .and_then(|d| d.parse::<u32>().ok())
+                    .ok_or(error("Section must be named dayNN"))?;
+                day = Some(number);
+                continue;
+            }
+
+            let day = day.ok_or(error("Answer outside a [dayNN] section"))?;
+            let (key, value) = line.split_once('=').ok_or(error("Expected key = value"))?;
+            let part = match key.trim() {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(error("Key must be part1 or part2")),
+            };
+            let value = Answers::parse_value(value.trim()).ok_or(error("Invalid value"))?;
+            answers.insert((day, part), value);
+        }
+        Ok(Answers(answers))
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<&str> {
+        self.0.get(&(day, part)).map(|s| s.as_str())
+    }
+
+    // Either a bare integer or a double quoted string with \n, \" and \\ escapes.
+    fn parse_value(value: &str) -> Option<String> {
+        let quoted = match value.strip_prefix('"') {
+            Some(quoted) => quoted.strip_suffix('"')?,
+            None => {
+                let is_integer = value
+                    .strip_prefix('-')
+                    .unwrap_or(value)
+                    .chars()
+                    .all(|c| c.is_ascii_digit());
+                return (is_integer && !value.is_empty()).then(|| value.to_owned());
+            }
+        };
+
+        let mut result = String::new();
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next()? {
+                    'n' => result.push('\n'),
+                    '"' => result.push('"'),
+                    '\\' => result.push('\\'),
+                    _ => return None,
+                },
+                '"' => return None,
+                _ => result.push(c),
+            }
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::Answers;
+
+    #[test]
+    fn parse() -> Result<()> {
+        let input = "# comment\n[day01]\npart1 = 1709\npart2 = \"1761\"\n\n[day13]\npart2 = \"#.\\n.#\\n\"\n";
+        let answers = Answers::new(input)?;
+        assert_eq!(answers.get(1, 1), Some("1709"));
+        assert_eq!(answers.get(1, 2), Some("1761"));
+        assert_eq!(answers.get(13, 1), None);
+        assert_eq!(answers.get(13, 2), Some("#.\n.#\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Answers::new("part1 = 1").is_err());
+        assert!(Answers::new("[day01\npart1 = 1").is_err());
+        assert!(Answers::new("[one]\npart1 = 1").is_err());
+        assert!(Answers::new("[day01]\npart3 = 1").is_err());
+        assert!(Answers::new("[day01]\npart1 = abc").is_err());
+        assert!(Answers::new("[day01]\npart1 = \"abc").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+/// A simulation that advances one generation at a time.
+pub trait Automaton {
+    /// What happened during a step.
+    type Report;
+
+    fn step(&mut self) -> Self::Report;
+
+    /// Steps forever, yielding each generation's report.
+    fn generations(&mut self) -> Generations<'_, Self>
+    where
+        Self: Sized,
+    {
+        Generations(self)
+    }
+
+    /// Steps until `done` accepts a report. Returns how many steps that took,
+    /// counting the last one, and its report.
+    fn run_until(&mut self, mut done: impl FnMut(&Self::Report) -> bool) -> (usize, Self::Report)
+    where
+        Self: Sized,
+    {
+        let mut steps = 0;
+        loop {
+            let report = self.step();
+            steps += 1;
+            if done(&report) {
+                return (steps, report);
+            }
+        }
+    }
+}
+
+/// Returned by `Automaton::generations`.
+pub struct Generations<'a, A>(&'a mut A);
+
+impl<A: Automaton> Iterator for Generations<'_, A> {
+    type Item = A::Report;
+
+    fn next(&mut self) -> Option<A::Report> {
+        Some(self.0.step())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Automaton;
+
+    // Doubles each step, reporting the new value.
+    struct Doubler(u32);
+
+    impl Automaton for Doubler {
+        type Report = u32;
+
+        fn step(&mut self) -> u32 {
+            self.0 *= 2;
+            self.0
+        }
+    }
+
+    #[test]
+    fn generations() {
+        let mut doubler = Doubler(1);
+        let first = doubler.generations().take(3).collect::<Vec<u32>>();
+        assert_eq!(first, [2, 4, 8]);
+        assert_eq!(doubler.step(), 16);
+    }
+
+    #[test]
+    fn run_until() {
+        let mut doubler = Doubler(1);
+        assert_eq!(doubler.run_until(|n| *n > 100), (7, 128));
+        assert_eq!(doubler.run_until(|_| true), (1, 256));
+    }
+}
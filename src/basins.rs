@@ -0,0 +1,154 @@
+use std::{cmp::Reverse, fmt::Write};
+
+use crate::{
+    matrix::{Grid, Matrix, Point},
+    search,
+};
+
+/// A region of a `Matrix` bounded by walls and the edges.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Basin {
+    pub id: usize,
+    pub points: Vec<Point>,
+    /// The lowest point in the basin. Ties go to the first point found.
+    pub low_point: Point,
+}
+
+impl Basin {
+    pub fn size(&self) -> usize {
+        self.points.len()
+    }
+}
+
+/// Every basin in a matrix, and which basin each cell belongs to.
+pub struct Basins {
+    pub basins: Vec<Basin>,
+    labels: Grid<Option<usize>>,
+}
+
+// Labels for up to 62 basins, after which they repeat.
+const LABELS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+// ANSI foreground colours, skipping black and white.
+const COLOURS: &[u8] = &[31, 32, 33, 34, 35, 36, 91, 92, 93, 94, 95, 96];
+
+impl Basins {
+    /// Splits `matrix` into basins, separated by cells whose value `is_wall`.
+    pub fn new(matrix: &Matrix, is_wall: impl Fn(u32) -> bool) -> Basins {
+        let in_basin = |point: &Point| !is_wall(*matrix.value(point).unwrap());
+        let components =
+            search::connected_components(matrix.all_points().filter(in_basin), |point| {
+                matrix.neighbours(*point).filter(in_basin)
+            });
+
+        let mut labels = Grid::filled(matrix.max_x, matrix.max_y, None);
+        let basins = components
+            .into_iter()
+            .enumerate()
+            .map(|(id, points)| {
+                for point in &points {
+                    labels.set(point, Some(id));
+                }
+                let low_point = *points
+                    .iter()
+                    .min_by_key(|point| matrix.value(point).unwrap())
+                    .unwrap();
+                Basin {
+                    id,
+                    points,
+                    low_point,
+                }
+            })
+            .collect();
+        Basins { basins, labels }
+    }
+
+    /// The id of the basin `point` is in, or `None` for walls.
+    pub fn label(&self, point: &Point) -> Option<usize> {
+        self.labels.value(point).copied().flatten()
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Basin> {
+        self.basins.get(id)
+    }
+
+    /// Basins from biggest to smallest.
+    pub fn by_size(&self) -> Vec<&Basin> {
+        let mut basins = self.basins.iter().collect::<Vec<&Basin>>();
+        basins.sort_by_key(|basin| Reverse(basin.size()));
+        basins
+    }
+
+    /// A map with one letter per basin and `#` for walls. With `colour`, each
+    /// basin's values are shown in its own colour instead, for terminals.
+    pub fn render(&self, matrix: &Matrix, colour: bool) -> String {
+        let mut result = String::new();
+        for y in 0..matrix.max_y {
+            for x in 0..matrix.max_x {
+                let point = Point { x, y };
+                match (self.label(&point), colour) {
+                    (None, false) => result.push('#'),
+                    (None, true) => {
+                        write!(result, "\x1b[2m{}\x1b[0m", matrix.value(&point).unwrap()).unwrap()
+                    }
+                    (Some(id), false) => result.push(LABELS[id % LABELS.len()] as char),
+                    (Some(id), true) => {
+                        let colour = COLOURS[id % COLOURS.len()];
+                        let value = matrix.value(&point).unwrap();
+                        write!(result, "\x1b[{}m{}\x1b[0m", colour, value).unwrap();
+                    }
+                }
+            }
+            result.push('\n');
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::Basins;
+    use crate::matrix::{Matrix, Point};
+
+    #[test]
+    fn basins() -> Result<()> {
+        let matrix = Matrix::new("219\n993\n945")?;
+        let basins = Basins::new(&matrix, |value| value == 9);
+        assert_eq!(basins.basins.len(), 2);
+
+        let first = basins.get(0).unwrap();
+        assert_eq!(first.size(), 2);
+        assert_eq!(first.low_point, Point { x: 1, y: 0 });
+        let second = basins.get(1).unwrap();
+        assert_eq!(second.size(), 3);
+        assert_eq!(second.low_point, Point { x: 2, y: 1 });
+
+        assert_eq!(basins.label(&Point { x: 0, y: 0 }), Some(0));
+        assert_eq!(basins.label(&Point { x: 2, y: 0 }), None);
+        assert_eq!(basins.label(&Point { x: 1, y: 2 }), Some(1));
+        assert_eq!(basins.by_size()[0].id, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn wall_predicate() -> Result<()> {
+        let matrix = Matrix::new("219\n993\n945")?;
+        let basins = Basins::new(&matrix, |value| value >= 4);
+        assert_eq!(basins.basins.len(), 2);
+        assert_eq!(basins.by_size()[0].size(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn render() -> Result<()> {
+        let matrix = Matrix::new("219\n993\n945")?;
+        let basins = Basins::new(&matrix, |value| value == 9);
+        assert_eq!(basins.render(&matrix, false), "aa#\n##b\n#bb\n");
+        assert!(basins
+            .render(&matrix, true)
+            .starts_with("\x1b[31m2\x1b[0m\x1b[31m1\x1b[0m\x1b[2m9\x1b[0m"));
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use std::{fmt::Write, time::Duration};
+
+use anyhow::{anyhow, Result};
+
+use crate::solution::Runner;
+
+/// Timings for one part, run `iterations` times.
+pub struct Bench {
+    pub day: u32,
+    pub part: u32,
+    pub iterations: usize,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn new(mut samples: Vec<Duration>) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+        // Nearest rank, so a single sample is its own median and p95.
+        let rank = |percentile: usize| (samples.len() * percentile).div_ceil(100) - 1;
+        Some(Stats {
+            min: samples[0],
+            median: samples[rank(50)],
+            p95: samples[rank(95)],
+        })
+    }
+}
+
+pub fn bench(solution: &dyn Runner, part: u32, input: &str, iterations: usize) -> Result<Bench> {
+    let mut parse_times = Vec::with_capacity(iterations);
+    let mut solve_times = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let run = solution.timed_run(part, input)?;
+        parse_times.push(run.parse_time);
+        solve_times.push(run.solve_time);
+    }
+
+    Ok(Bench {
+        day: solution.day(),
+        part,
+        iterations,
+        parse: Stats::new(parse_times).ok_or(anyhow!("Need at least one iteration"))?,
+        solve: Stats::new(solve_times).ok_or(anyhow!("Need at least one iteration"))?,
+    })
+}
+
+pub fn to_table(benches: &[Bench]) -> String {
+    let mut result = format!(
+        "{:>3}  {:>4}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}\n",
+        "Day", "Part", "parse min", "parse med", "parse p95", "solve min", "solve med", "solve p95"
+    );
+    for b in benches {
+        let times = [
+            b.parse.min,
+            b.parse.median,
+            b.parse.p95,
+            b.solve.min,
+            b.solve.median,
+            b.solve.p95,
+        ];
+        write!(result, "{:>3}  {:>4}", b.day, b.part).unwrap();
+        for time in times {
+            write!(result, "  {:>10}", format!("{:.2?}", time)).unwrap();
+        }
+        result.push('\n');
+    }
+    result
+}
+
+// Times are in nanoseconds, so runs from different commits can be diffed directly.
+pub fn to_csv(benches: &[Bench]) -> String {
+    let mut result = String::from(
+        "day,part,iterations,parse_min_ns,parse_median_ns,parse_p95_ns,solve_min_ns,solve_median_ns,solve_p95_ns\n",
+    );
+    for b in benches {
+        writeln!(
+            result,
+            "{},{},{},{},{},{},{},{},{}",
+            b.day,
+            b.part,
+            b.iterations,
+            b.parse.min.as_nanos(),
+            b.parse.median.as_nanos(),
+            b.parse.p95.as_nanos(),
+            b.solve.min.as_nanos(),
+            b.solve.median.as_nanos(),
+            b.solve.p95.as_nanos()
+        )
+        .unwrap();
+    }
+    result
+}
+
+pub fn to_json(benches: &[Bench]) -> String {
+    let stats = |s: &Stats| {
+        format!(
+            "{{\"min_ns\": {}, \"median_ns\": {}, \"p95_ns\": {}}}",
+            s.min.as_nanos(),
+            s.median.as_nanos(),
+            s.p95.as_nanos()
+        )
+    };
+    let entries = benches
+        .iter()
+        .map(|b| {
+            format!(
+                "  {{\"day\": {}, \"part\": {}, \"iterations\": {}, \"parse\": {}, \"solve\": {}}}",
+                b.day,
+                b.part,
+                b.iterations,
+                stats(&b.parse),
+                stats(&b.solve)
+            )
+        })
+        .collect::<Vec<String>>();
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Bench, Stats};
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn stats() {
+        let samples = (1..=20).rev().map(ms).collect();
+        let stats = Stats::new(samples).unwrap();
+        assert_eq!(stats.min, ms(1));
+        assert_eq!(stats.median, ms(10));
+        assert_eq!(stats.p95, ms(19));
+
+        let stats = Stats::new(vec![ms(3)]).unwrap();
+        assert_eq!(stats.min, ms(3));
+        assert_eq!(stats.median, ms(3));
+        assert_eq!(stats.p95, ms(3));
+
+        assert!(Stats::new(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn formats() {
+        let stats = Stats::new(vec![ms(1), ms(2)]).unwrap();
+        let benches = [Bench {
+            day: 7,
+            part: 2,
+            iterations: 2,
+            parse: stats,
+            solve: stats,
+        }];
+
+        let csv = super::to_csv(&benches);
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("7,2,2,1000000,1000000,2000000,1000000,1000000,2000000")
+        );
+
+        let json = super::to_json(&benches);
+        assert!(json.starts_with(
+            "[\n  {\"day\": 7, \"part\": 2, \"iterations\": 2, \"parse\": {\"min_ns\": 1000000,"
+        ));
+        assert!(json.ends_with("}}\n]\n"));
+    }
+}
@@ -0,0 +1,168 @@
+use std::{cmp::Ordering, fmt::Display};
+
+/// An unsigned integer of any size, with just enough arithmetic for
+/// evaluating packets.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BigUint {
+    // Base 2^32, least significant first, with no trailing zeroes.
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint::default()
+    }
+
+    /// Builds a number from hex digits, most significant first.
+    pub fn from_nibbles(nibbles: &[u8]) -> BigUint {
+        let mut result = BigUint::zero();
+        for nibble in nibbles {
+            result.mul_add_small(16, *nibble as u32);
+        }
+        result
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [low] => Some(low as u64),
+            [low, high] => Some(((high as u64) << 32) | low as u64),
+            _ => None,
+        }
+    }
+
+    pub fn add(&self, other: &BigUint) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len().max(other.limbs.len()) + 1);
+        let mut carry = 0u64;
+        for i in 0..self.limbs.len().max(other.limbs.len()) {
+            let sum = carry
+                + *self.limbs.get(i).unwrap_or(&0) as u64
+                + *other.limbs.get(i).unwrap_or(&0) as u64;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+        BigUint::normalised(limbs)
+    }
+
+    pub fn mul(&self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let product = limbs[i + j] as u64 + *a as u64 * *b as u64 + carry;
+                limbs[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint::normalised(limbs)
+    }
+
+    // self = self * factor + addend
+    fn mul_add_small(&mut self, factor: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for limb in self.limbs.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    // Divides in place, returning the remainder.
+    fn div_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let current = (remainder << 32) | *limb as u64;
+            *limb = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        *self = BigUint::normalised(std::mem::take(&mut self.limbs));
+        remainder as u32
+    }
+
+    fn normalised(mut limbs: Vec<u32>) -> BigUint {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        BigUint::normalised(vec![value as u32, (value >> 32) as u32])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Nine decimal digits at a time, least significant first.
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while rest != BigUint::zero() {
+            chunks.push(rest.div_small(1_000_000_000));
+        }
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{}", first)?;
+                rest.iter()
+                    .rev()
+                    .try_for_each(|chunk| write!(f, "{:09}", chunk))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BigUint;
+
+    #[test]
+    fn arithmetic() {
+        let max = BigUint::from(u64::MAX);
+        assert_eq!(max.to_u64(), Some(u64::MAX));
+        assert_eq!(max.to_string(), "18446744073709551615");
+
+        let sum = max.add(&BigUint::from(1));
+        assert_eq!(sum.to_u64(), None);
+        assert_eq!(sum.to_string(), "18446744073709551616");
+
+        let square = max.mul(&max);
+        assert_eq!(
+            square.to_string(),
+            "340282366920938463426481119284349108225"
+        );
+        assert!(square > sum && sum > max);
+        assert_eq!(BigUint::from(0).mul(&max), BigUint::zero());
+        assert_eq!(BigUint::zero().to_string(), "0");
+    }
+
+    #[test]
+    fn from_nibbles() {
+        assert_eq!(BigUint::from_nibbles(&[0, 7, 14, 5]).to_u64(), Some(2021));
+        let wide = BigUint::from_nibbles(&[1; 17]);
+        assert_eq!(wide.to_string(), "19676527011956855057");
+        let wider = BigUint::from_nibbles(&[15; 20]);
+        assert_eq!(wider.to_string(), "1208925819614629174706175");
+    }
+}
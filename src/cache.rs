@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::files;
+
+pub const YEAR: u32 = 2021;
+
+/// Overrides the directory inputs are cached in.
+pub const CACHE_DIR_VAR: &str = "AOC2021_CACHE";
+
+/// Somewhere a puzzle input can be fetched from when it isn't cached yet.
+pub trait Fetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String>;
+}
+
+/// Fetches inputs from a directory of `day<day>.txt` files, like `inputs/`.
+pub struct LocalFetcher {
+    dir: PathBuf,
+}
+
+impl LocalFetcher {
+    pub fn new(dir: impl Into<PathBuf>) -> LocalFetcher {
+        LocalFetcher { dir: dir.into() }
+    }
+}
+
+impl Fetcher for LocalFetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String> {
+        if year != YEAR {
+            return Err(anyhow!("{} only holds {} inputs", self.dir.display(), YEAR));
+        }
+        let path = self.dir.join(format!("day{}.txt", day));
+        std::fs::read_to_string(&path).with_context(|| format!("Reading {}", path.display()))
+    }
+}
+
+/// Stands in for downloading from adventofcode.com, which needs a session
+/// cookie and network access. It only says where the input would come from.
+pub struct HttpStub;
+
+impl HttpStub {
+    pub fn url(year: u32, day: u32) -> String {
+        format!("https://adventofcode.com/{}/day/{}/input", year, day)
+    }
+}
+
+impl Fetcher for HttpStub {
+    fn fetch(&self, year: u32, day: u32) -> Result<String> {
+        Err(anyhow!(
+            "Downloading inputs isn't supported, save {} into the cache",
+            HttpStub::url(year, day)
+        ))
+    }
+}
+
+/// Inputs stored as `<dir>/<year>/day<day>.txt`, fetched on first use.
+pub struct Cache<F: Fetcher> {
+    dir: PathBuf,
+    fetcher: F,
+}
+
+impl<F: Fetcher> Cache<F> {
+    pub fn new(dir: impl Into<PathBuf>, fetcher: F) -> Cache<F> {
+        Cache {
+            dir: dir.into(),
+            fetcher,
+        }
+    }
+
+    pub fn path(&self, year: u32, day: u32) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("day{}.txt", day))
+    }
+
+    /// Returns the cached input, fetching and storing it first if needed.
+    /// Fetched inputs are normalised, and anything returned has been validated.
+    pub fn get(&self, year: u32, day: u32) -> Result<String> {
+        let path = self.path(year, day);
+        let file_name = path.display().to_string();
+        let input = if path.exists() {
+            std::fs::read_to_string(&path).with_context(|| format!("Reading {}", file_name))?
+        } else {
+            let input = files::normalise(&self.fetcher.fetch(year, day)?);
+            files::validate(&input).map_err(|e| e.in_file(&format!("fetched day {}", day)))?;
+            store(&path, &input)?;
+            input
+        };
+        files::validate(&input).map_err(|e| e.in_file(&file_name))?;
+        Ok(input)
+    }
+}
+
+/// `$AOC2021_CACHE` if set, otherwise `aoc` in the user's cache directory.
+pub fn default_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(CACHE_DIR_VAR) {
+        return Some(PathBuf::from(dir));
+    }
+    let cache_home = match std::env::var_os("XDG_CACHE_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+    };
+    Some(cache_home.join("aoc"))
+}
+
+fn store(path: &Path, input: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Creating {}", dir.display()))?;
+    }
+    std::fs::write(path, input).with_context(|| format!("Writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, collections::HashMap, path::PathBuf};
+
+    use anyhow::{anyhow, Result};
+
+    use super::{Cache, Fetcher, HttpStub, LocalFetcher, YEAR};
+
+    struct MapFetcher {
+        inputs: HashMap<u32, &'static str>,
+        fetches: Cell<usize>,
+    }
+
+    impl Fetcher for MapFetcher {
+        fn fetch(&self, _year: u32, day: u32) -> Result<String> {
+            self.fetches.set(self.fetches.get() + 1);
+            self.inputs
+                .get(&day)
+                .map(|input| input.to_string())
+                .ok_or(anyhow!("No input for day {}", day))
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc2021-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn fetches_once() -> Result<()> {
+        let dir = temp_dir("fetches-once");
+        let fetcher = MapFetcher {
+            inputs: HashMap::from([(1, "1\r\n2\r\n"), (2, "\n")]),
+            fetches: Cell::new(0),
+        };
+        let cache = Cache::new(&dir, fetcher);
+
+        assert_eq!(cache.get(YEAR, 1)?, "1\n2");
+        assert_eq!(cache.get(YEAR, 1)?, "1\n2");
+        assert_eq!(cache.fetcher.fetches.get(), 1);
+        assert_eq!(std::fs::read_to_string(dir.join("2021/day1.txt"))?, "1\n2");
+
+        assert!(cache.get(YEAR, 2).is_err());
+        assert!(!cache.path(YEAR, 2).exists());
+
+        std::fs::write(cache.path(YEAR, 3), "")?;
+        assert!(cache.get(YEAR, 3).is_err());
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn local_fetcher() -> Result<()> {
+        let fetcher = LocalFetcher::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("inputs"));
+        assert!(fetcher
+            .fetch(YEAR, 21)?
+            .starts_with("Player 1 starting position"));
+        assert!(fetcher.fetch(2020, 21).is_err());
+        assert!(HttpStub.fetch(YEAR, 1).is_err());
+        Ok(())
+    }
+}
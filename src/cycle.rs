@@ -0,0 +1,173 @@
+use std::{collections::HashMap, hash::Hash};
+
+// A simulation here is an initial state and a step function. State `n` is the
+// state after `n` steps, so the initial state is state 0.
+
+/// State `start` is the first state to be repeated, and it comes round again
+/// every `period` steps from then on.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The earliest state that's equal to state `n`.
+    pub fn earliest_equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Floyd's tortoise and hare. Only keeps two states around, but steps roughly
+/// three times as often as the hash history does. Never returns if there's no cycle.
+pub fn floyd<S: Eq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+    Cycle { start, period }
+}
+
+/// Brent's algorithm. Like `floyd` it only keeps two states around, but finds
+/// the period with fewer steps. Never returns if there's no cycle.
+pub fn brent<S: Eq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Start the hare `period` steps ahead, then both meet at the start of the cycle.
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, period }
+}
+
+/// Remembers every state, so each is only computed once. Gives up after
+/// `limit` steps without a repeat, by which point it's holding `limit + 1`
+/// states.
+pub fn hash_history<S: Eq + Hash + Clone>(
+    initial: S,
+    step: impl FnMut(&S) -> S,
+    limit: usize,
+) -> Option<Cycle> {
+    let (_, cycle) = history(initial, step, limit);
+    cycle
+}
+
+/// State `n`, skipping round any cycle found on the way instead of stepping
+/// through it again and again. Every state up to the first repeat is kept, so
+/// if nothing repeats this holds all `n + 1` states at once. When a cycle is
+/// certain but memory is tight, find it with `brent` and step to
+/// `earliest_equivalent(n)` instead.
+pub fn state_at<S: Eq + Hash + Clone>(initial: S, step: impl FnMut(&S) -> S, n: usize) -> S {
+    let (mut states, cycle) = history(initial, step, n);
+    let n = match cycle {
+        Some(cycle) => cycle.earliest_equivalent(n),
+        None => n,
+    };
+    states.swap_remove(n)
+}
+
+// Every state up to the first repeat, or up to state `limit` if nothing repeats.
+fn history<S: Eq + Hash + Clone>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    limit: usize,
+) -> (Vec<S>, Option<Cycle>) {
+    let mut seen = HashMap::from([(initial.clone(), 0)]);
+    let mut states = vec![initial];
+    while states.len() <= limit {
+        let next = step(states.last().unwrap());
+        if let Some(start) = seen.get(&next) {
+            let cycle = Cycle {
+                start: *start,
+                period: states.len() - start,
+            };
+            return (states, Some(cycle));
+        }
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+    (states, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cycle;
+
+    // 0, 1, 2, 3, 4, 5, 6, 7, 3, 4, ...
+    fn step(n: &u32) -> u32 {
+        if *n == 7 {
+            3
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn finds_the_same_cycle() {
+        let expected = Cycle {
+            start: 3,
+            period: 5,
+        };
+        assert_eq!(super::floyd(0, step), expected);
+        assert_eq!(super::brent(0, step), expected);
+        assert_eq!(super::hash_history(0, step, 100), Some(expected));
+        assert_eq!(super::hash_history(0, step, 5), None);
+
+        let from_cycle = Cycle {
+            start: 0,
+            period: 5,
+        };
+        assert_eq!(super::floyd(5, step), from_cycle);
+        assert_eq!(super::brent(5, step), from_cycle);
+    }
+
+    #[test]
+    fn state_at() {
+        assert_eq!(super::state_at(0, step, 2), 2);
+        assert_eq!(super::state_at(0, step, 8), 3);
+        assert_eq!(super::state_at(0, step, 1_000_000_000), 5);
+        assert_eq!(super::state_at(0u64, |n| n + 1, 50), 50);
+    }
+}
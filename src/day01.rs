@@ -1,4 +1,7 @@
-#[allow(dead_code)]
+use anyhow::Result;
+
+use crate::solution::Solution;
+
 fn number_of_increases(input: &[i32]) -> usize {
     input
         .iter()
@@ -7,7 +10,6 @@ fn number_of_increases(input: &[i32]) -> usize {
         .count()
 }
 
-#[allow(dead_code)]
 fn num_increasing_windows(input: &[i32]) -> usize {
     let window_sums = create_three_element_windows(input);
     number_of_increases(&window_sums)
@@ -21,36 +23,54 @@ fn create_three_element_windows(input: &[i32]) -> Vec<i32> {
         .collect()
 }
 
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u32 = 1;
+
+    type Input = Vec<i32>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(crate::files::parse_numbers_strict(input)?)
+    }
+
+    fn part_1(input: Self::Input) -> Result<Self::Answer1> {
+        Ok(number_of_increases(&input))
+    }
+
+    fn part_2(input: Self::Input) -> Result<Self::Answer2> {
+        Ok(num_increasing_windows(&input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
 
     #[test]
     fn part_1_test() -> Result<()> {
-        test("inputs/day1-test.txt", &super::number_of_increases, 7)
+        test("day1-test", &super::number_of_increases, 7)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day1.txt", &super::number_of_increases, 1709)
+        test("day1", &super::number_of_increases, 1709)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test("inputs/day1-test.txt", &super::num_increasing_windows, 5)
+        test("day1-test", &super::num_increasing_windows, 5)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test("inputs/day1.txt", &super::num_increasing_windows, 1761)
+        test("day1", &super::num_increasing_windows, 1761)
     }
 
-    fn test(
-        test_file: &str,
-        function: &dyn Fn(&[i32]) -> usize,
-        expected_val: usize,
-    ) -> Result<()> {
-        let input = crate::files::read_numbers(test_file)?;
+    fn test(name: &str, function: &dyn Fn(&[i32]) -> usize, expected_val: usize) -> Result<()> {
+        let input = crate::files::parse_numbers(&crate::input::read(name)?);
         let result = function(&input);
         assert_eq!(result, expected_val);
         Ok(())
@@ -1,6 +1,8 @@
 use anyhow::{anyhow, Result};
 
-struct Submarine {
+use crate::solution::Solution;
+
+pub struct Submarine {
     x: i32,
     y: i32,
     aim: i32,
@@ -31,7 +33,6 @@ impl Instruction {
 }
 
 impl Submarine {
-    #[allow(dead_code)]
     fn new(input: &[String]) -> Result<Submarine> {
         let instructions = input
             .iter()
@@ -45,7 +46,6 @@ impl Submarine {
         })
     }
 
-    #[allow(dead_code)]
     fn execute_all_instructions_1(&mut self) {
         self.instructions
             .iter()
@@ -56,7 +56,6 @@ impl Submarine {
             })
     }
 
-    #[allow(dead_code)]
     fn execute_all_instructions_2(&mut self) {
         self.instructions
             .iter()
@@ -70,12 +69,35 @@ impl Submarine {
             })
     }
 
-    #[allow(dead_code)]
     fn final_position(&self) -> i32 {
         self.x * self.y
     }
 }
 
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u32 = 2;
+
+    type Input = Submarine;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Submarine::new(&crate::files::parse_lines(input))
+    }
+
+    fn part_1(mut submarine: Self::Input) -> Result<Self::Answer1> {
+        submarine.execute_all_instructions_1();
+        Ok(submarine.final_position())
+    }
+
+    fn part_2(mut submarine: Self::Input) -> Result<Self::Answer2> {
+        submarine.execute_all_instructions_2();
+        Ok(submarine.final_position())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
@@ -85,7 +107,7 @@ mod tests {
     #[test]
     fn part_1_test() -> Result<()> {
         test(
-            "inputs/day2-test.txt",
+            "day2-test",
             &super::Submarine::execute_all_instructions_1,
             150,
         )
@@ -94,7 +116,7 @@ mod tests {
     #[test]
     fn part_1_real() -> Result<()> {
         test(
-            "inputs/day2.txt",
+            "day2",
             &super::Submarine::execute_all_instructions_1,
             2322630,
         )
@@ -103,7 +125,7 @@ mod tests {
     #[test]
     fn part_2_test() -> Result<()> {
         test(
-            "inputs/day2-test.txt",
+            "day2-test",
             &super::Submarine::execute_all_instructions_2,
             900,
         )
@@ -112,14 +134,14 @@ mod tests {
     #[test]
     fn part_2_real() -> Result<()> {
         test(
-            "inputs/day2.txt",
+            "day2",
             &super::Submarine::execute_all_instructions_2,
             2105273490,
         )
     }
 
-    fn test(test_file: &str, function: &dyn Fn(&mut Submarine), expected_val: i32) -> Result<()> {
-        let input = crate::files::read_lines(test_file)?;
+    fn test(name: &str, function: &dyn Fn(&mut Submarine), expected_val: i32) -> Result<()> {
+        let input = crate::files::parse_lines(&crate::input::read(name)?);
         let mut submarine = Submarine::new(&input)?;
         function(&mut submarine);
         assert_eq!(submarine.final_position(), expected_val);
@@ -1,19 +1,49 @@
-#[allow(dead_code)]
+use crate::{files::InputError, solution::Solution};
+
+// Every line must be a binary number as wide as the first, so the ratings
+// below can index the first line and parse the result.
+fn parse_report(input: &str) -> Result<Vec<String>, InputError> {
+    let mut width = None;
+    let mut report = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        if let Some(column) = line.find(|c| c != '0' && c != '1') {
+            let text = line[column..].chars().take(1).collect::<String>();
+            return Err(InputError::new(
+                i + 1,
+                column + 1,
+                &text,
+                "Unexpected character",
+            ));
+        }
+        let expected = *width.get_or_insert(line.len());
+        if line.is_empty() || line.len() > u32::BITS as usize {
+            let reason = format!("Expected 1 to {} digits", u32::BITS);
+            return Err(InputError::new(i + 1, 1, line, &reason));
+        }
+        if line.len() != expected {
+            let reason = format!("Expected {} digits", expected);
+            return Err(InputError::new(i + 1, 1, line, &reason));
+        }
+        report.push(line.to_owned());
+    }
+    if report.is_empty() {
+        return Err(InputError::new(1, 1, "", "Empty report"));
+    }
+    Ok(report)
+}
+
 fn gamma(input: &[String]) -> anyhow::Result<u32> {
     process(input, &most_common_digit)
 }
 
-#[allow(dead_code)]
 fn epsilon(input: &[String]) -> anyhow::Result<u32> {
     process(input, &least_common_digit)
 }
 
-#[allow(dead_code)]
 fn oxygen_rating(input: &[String]) -> anyhow::Result<u32> {
     process_repeatedly(input, &most_common_digit)
 }
 
-#[allow(dead_code)]
 fn co2_rating(input: &[String]) -> anyhow::Result<u32> {
     process_repeatedly(input, &least_common_digit)
 }
@@ -40,13 +70,10 @@ fn process_repeatedly(
         }
         let line = nth_line(&temp, index);
         let digit = process_fn(&line);
-        temp = temp
-            .into_iter()
-            .filter(|line| match line.chars().nth(index) {
-                Some(c) => c == digit,
-                None => false,
-            })
-            .collect();
+        temp.retain(|line| match line.chars().nth(index) {
+            Some(c) => c == digit,
+            None => false,
+        });
     }
     let result = u32::from_str_radix(&temp[0], 2)?;
     Ok(result)
@@ -106,30 +133,48 @@ fn counts(input: &[char]) -> (u32, u32) {
     })
 }
 
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u32 = 3;
+
+    type Input = Vec<String>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_report(input)?)
+    }
+
+    fn part_1(input: Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(gamma(&input)? * epsilon(&input)?)
+    }
+
+    fn part_2(input: Self::Input) -> anyhow::Result<Self::Answer2> {
+        Ok(oxygen_rating(&input)? * co2_rating(&input)?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
 
+    use crate::files::InputError;
+
     #[test]
     fn part_1_test() -> Result<()> {
-        test(
-            "inputs/day3-test.txt",
-            &super::gamma,
-            22,
-            &super::epsilon,
-            9,
-        )
+        test("day3-test", &super::gamma, 22, &super::epsilon, 9)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day3.txt", &super::gamma, 199, &super::epsilon, 3896)
+        test("day3", &super::gamma, 199, &super::epsilon, 3896)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
         test(
-            "inputs/day3-test.txt",
+            "day3-test",
             &super::oxygen_rating,
             23,
             &super::co2_rating,
@@ -139,23 +184,40 @@ mod tests {
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test(
-            "inputs/day3.txt",
-            &super::oxygen_rating,
-            509,
-            &super::co2_rating,
-            2693,
-        )
+        test("day3", &super::oxygen_rating, 509, &super::co2_rating, 2693)
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |input| super::parse_report(input).err();
+        assert_eq!(error(""), Some(InputError::new(1, 1, "", "Empty report")));
+        assert_eq!(
+            error("0101\n01x1"),
+            Some(InputError::new(2, 3, "x", "Unexpected character"))
+        );
+        assert_eq!(
+            error("0101\n011"),
+            Some(InputError::new(2, 1, "011", "Expected 4 digits"))
+        );
+        assert_eq!(
+            error(&"1".repeat(33)),
+            Some(InputError::new(
+                1,
+                1,
+                &"1".repeat(33),
+                "Expected 1 to 32 digits"
+            ))
+        );
     }
 
     fn test(
-        test_file: &str,
+        name: &str,
         function_one: &dyn Fn(&[String]) -> Result<u32>,
         expected_one: u32,
         function_two: &dyn Fn(&[String]) -> Result<u32>,
         expected_two: u32,
     ) -> Result<()> {
-        let input = crate::files::read_lines(test_file)?;
+        let input = super::parse_report(&crate::input::read(name)?)?;
         assert_eq!(function_one(&input)?, expected_one);
         assert_eq!(function_two(&input)?, expected_two);
         Ok(())
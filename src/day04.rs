@@ -2,14 +2,18 @@ use std::{collections::HashMap, num::ParseIntError};
 
 use anyhow::{anyhow, Result};
 
-struct BingoGame {
+use crate::{
+    matrix::{Grid, Point},
+    solution::Solution,
+};
+
+pub struct BingoGame {
     boards: Vec<BingoBoard>,
     called_numbers: Vec<u32>,
     completions: Vec<u32>,
 }
 
 impl BingoGame {
-    #[allow(dead_code)]
     fn new(input: &str) -> Result<BingoGame> {
         let mut parts = input.split("\n\n");
         let first_line = parts.next().ok_or(anyhow!("Invalid input"))?;
@@ -30,7 +34,6 @@ impl BingoGame {
         })
     }
 
-    #[allow(dead_code)]
     fn call_all_numbers(&mut self) {
         for number in self.called_numbers.iter() {
             for board in self.boards.iter_mut() {
@@ -45,15 +48,13 @@ impl BingoGame {
         }
     }
 
-    #[allow(dead_code)]
     fn winning_board(&self) -> Result<u32> {
         self.completions
-            .get(0)
+            .first()
             .copied()
             .ok_or(anyhow!("No winning board"))
     }
 
-    #[allow(dead_code)]
     fn losing_board(&self) -> Result<u32> {
         self.completions
             .iter()
@@ -65,80 +66,88 @@ impl BingoGame {
 
 #[derive(Debug)]
 struct BingoBoard {
-    number_to_cell: HashMap<u32, Cell>,
-    row_completions: HashMap<usize, usize>,
-    column_completions: HashMap<usize, usize>,
-    side_len: usize,
+    positions: HashMap<u32, Point>,
+    numbers: Grid<u32>,
+    marked: Grid<bool>,
     completed: bool,
 }
 
-#[derive(PartialEq, Eq, Debug)]
-struct Cell {
-    x: usize,
-    y: usize,
-    number: u32,
-    marked: bool,
-}
-
 impl BingoBoard {
     fn new(lines: &str) -> Result<BingoBoard> {
-        let mut number_to_cell = HashMap::new();
-        for (y, line) in lines.split('\n').enumerate() {
-            for (x, value) in line.split_ascii_whitespace().enumerate() {
-                let number = value.parse::<u32>()?;
-                let marked = false;
-                let cell = Cell {
-                    x,
-                    y,
-                    number,
-                    marked,
-                };
-                number_to_cell.insert(number, cell);
-            }
-        }
-        let row_completions = HashMap::new();
-        let column_completions = HashMap::new();
-        let side_len = lines.split('\n').count();
+        let rows = lines
+            .lines()
+            .map(|line| {
+                line.split_ascii_whitespace()
+                    .map(|value| value.parse::<u32>())
+                    .collect::<Result<Vec<u32>, ParseIntError>>()
+            })
+            .collect::<Result<Vec<Vec<u32>>, ParseIntError>>()?;
+        let numbers = Grid::from_rows(rows)?;
+        let positions = numbers
+            .all_points()
+            .map(|point| (*numbers.value(&point).unwrap(), point))
+            .collect();
+        let marked = Grid::filled(numbers.max_x, numbers.max_y, false);
         let completed = false;
         Ok(BingoBoard {
-            number_to_cell,
-            row_completions,
-            column_completions,
-            side_len,
+            positions,
+            numbers,
+            marked,
             completed,
         })
     }
 
     fn mark(&mut self, number: u32) {
-        let cell = match self.number_to_cell.get_mut(&number) {
-            Some(cell) => cell,
+        let point = match self.positions.get(&number) {
+            Some(point) => *point,
             None => return,
         };
-        if cell.marked {
+        let marked = self.marked.value_mut(&point).unwrap();
+        if *marked {
             // Cell was already called.
             return;
         }
-        cell.marked = true;
-
-        let row = self.row_completions.entry(cell.y).or_default();
-        let column = self.column_completions.entry(cell.x).or_default();
-        *row += 1;
-        *column += 1;
+        *marked = true;
 
-        if *row == self.side_len || *column == self.side_len {
+        if self.marked.row(point.y).iter().all(|m| *m) || self.marked.column(point.x).all(|m| *m) {
             self.completed = true
         }
     }
 
     fn unmarked_number_sum(&self) -> u32 {
-        self.number_to_cell
+        self.numbers
             .values()
-            .filter(|cell| !cell.marked)
-            .map(|cell| cell.number)
+            .zip(self.marked.values())
+            .filter(|(_, marked)| !**marked)
+            .map(|(number, _)| number)
             .sum()
     }
 }
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u32 = 4;
+
+    type Input = BingoGame;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        BingoGame::new(input)
+    }
+
+    fn part_1(mut bingo_game: Self::Input) -> Result<Self::Answer1> {
+        bingo_game.call_all_numbers();
+        bingo_game.winning_board()
+    }
+
+    fn part_2(mut bingo_game: Self::Input) -> Result<Self::Answer2> {
+        bingo_game.call_all_numbers();
+        bingo_game.losing_board()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
@@ -147,30 +156,35 @@ mod tests {
 
     #[test]
     fn part_1_test() -> Result<()> {
-        test("inputs/day4-test.txt", &BingoGame::winning_board, 4512)
+        test("day4-test", &BingoGame::winning_board, 4512)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day4.txt", &BingoGame::winning_board, 35711)
+        test("day4", &BingoGame::winning_board, 35711)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test("inputs/day4-test.txt", &BingoGame::losing_board, 1924)
+        test("day4-test", &BingoGame::losing_board, 1924)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test("inputs/day4.txt", &BingoGame::losing_board, 5586)
+        test("day4", &BingoGame::losing_board, 5586)
+    }
+
+    #[test]
+    fn trailing_newline() -> Result<()> {
+        let input = crate::input::read("day4-test")? + "\n";
+        let mut bingo_game = BingoGame::new(&input)?;
+        bingo_game.call_all_numbers();
+        assert_eq!(bingo_game.winning_board()?, 4512);
+        Ok(())
     }
 
-    fn test(
-        test_file: &str,
-        function: &dyn Fn(&BingoGame) -> Result<u32>,
-        expected: u32,
-    ) -> Result<()> {
-        let input = crate::files::read_string(test_file)?;
+    fn test(name: &str, function: &dyn Fn(&BingoGame) -> Result<u32>, expected: u32) -> Result<()> {
+        let input = crate::input::read(name)?;
         let mut bingo_game = BingoGame::new(&input)?;
         bingo_game.call_all_numbers();
         let result = function(&bingo_game)?;
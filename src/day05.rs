@@ -1,21 +1,58 @@
-use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use anyhow::Result;
 
-#[allow(dead_code)]
-fn num_dangerous_points(input: &[String], allow_diagonals: bool) -> usize {
-    let lines: Vec<Line> = input
+use crate::{
+    files::InputError,
+    solution::Solution,
+    sparse::{Point, SparseGrid},
+};
+
+fn num_dangerous_points(segments: &[Segment], allow_diagonals: bool) -> usize {
+    let lines: Vec<Line> = segments
         .iter()
-        .filter_map(|l| Line::new(l, allow_diagonals))
+        .filter_map(|segment| Line::new(segment, allow_diagonals))
         .collect();
-    let mut points: HashMap<Point, u32> = HashMap::new();
+    let mut vents = SparseGrid::new(0u32);
 
     for line in lines {
         for point in line {
-            *points.entry(point).or_default() += 1;
+            *vents.get_mut(point) += 1;
         }
     }
 
-    points.values().filter(|value| **value >= 2).count()
+    vents.values().filter(|value| **value >= 2).count()
+}
+
+#[derive(Clone, Copy)]
+pub struct Segment {
+    start: Point,
+    end: Point,
+}
+
+fn parse_segments(input: &str) -> Result<Vec<Segment>, InputError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_segment(i + 1, line))
+        .collect()
+}
+
+fn parse_segment(number: usize, line: &str) -> Result<Segment, InputError> {
+    let (start, end) = line
+        .split_once(" -> ")
+        .ok_or_else(|| InputError::new(number, 1, line, "Expected \"x1,y1 -> x2,y2\""))?;
+    let point = |text: &str, column| {
+        parse_point(text)
+            .ok_or_else(|| InputError::new(number, column, text, "Expected a point like \"x,y\""))
+    };
+    Ok(Segment {
+        start: point(start, 1)?,
+        end: point(end, start.len() + " -> ".len() + 1)?,
+    })
+}
+
+fn parse_point(s: &str) -> Option<Point> {
+    let (x, y) = s.split_once(',')?;
+    Some(Point::new(x.parse().ok()?, y.parse().ok()?))
 }
 
 struct Line {
@@ -27,10 +64,9 @@ struct Line {
 }
 
 impl Line {
-    fn new(line: &str, allow_diagonals: bool) -> Option<Line> {
-        let mut parts = line.split(" -> ");
-        let current = Point::new(parts.next()).ok()?;
-        let end = Point::new(parts.next()).ok()?;
+    // None for a diagonal segment when they aren't allowed.
+    fn new(segment: &Segment, allow_diagonals: bool) -> Option<Line> {
+        let (current, end) = (segment.start, segment.end);
 
         let ended = false;
 
@@ -90,25 +126,25 @@ impl Iterator for Line {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+pub struct Day05;
 
-impl Point {
-    fn new(input: Option<&str>) -> Result<Point> {
-        let input = input.ok_or(anyhow!("invalid point input"))?;
-        let mut parts = input.split(',');
-        let x = parts
-            .next()
-            .ok_or(anyhow!("invalid point"))?
-            .parse::<i32>()?;
-        let y = parts
-            .next()
-            .ok_or(anyhow!("invalid point"))?
-            .parse::<i32>()?;
-        Ok(Point { x, y })
+impl Solution for Day05 {
+    const DAY: u32 = 5;
+
+    type Input = Vec<Segment>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_segments(input)?)
+    }
+
+    fn part_1(input: Self::Input) -> Result<Self::Answer1> {
+        Ok(num_dangerous_points(&input, false))
+    }
+
+    fn part_2(input: Self::Input) -> Result<Self::Answer2> {
+        Ok(num_dangerous_points(&input, true))
     }
 }
 
@@ -116,43 +152,60 @@ impl Point {
 mod tests {
     use anyhow::Result;
 
+    use super::Segment;
+    use crate::files::InputError;
+
     #[test]
     fn part_1_test() -> Result<()> {
-        test(
-            "inputs/day5-test.txt",
-            &super::num_dangerous_points,
-            false,
-            5,
-        )
+        test("day5-test", &super::num_dangerous_points, false, 5)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day5.txt", &super::num_dangerous_points, false, 7269)
+        test("day5", &super::num_dangerous_points, false, 7269)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test(
-            "inputs/day5-test.txt",
-            &super::num_dangerous_points,
-            true,
-            12,
-        )
+        test("day5-test", &super::num_dangerous_points, true, 12)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test("inputs/day5.txt", &super::num_dangerous_points, true, 21140)
+        test("day5", &super::num_dangerous_points, true, 21140)
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |input| super::parse_segments(input).err();
+        assert_eq!(
+            error("0,9 -> 5,9\n8,0 => 0,8"),
+            Some(InputError::new(
+                2,
+                1,
+                "8,0 => 0,8",
+                "Expected \"x1,y1 -> x2,y2\""
+            ))
+        );
+        assert_eq!(
+            error("0,9 -> 5,x"),
+            Some(InputError::new(
+                1,
+                8,
+                "5,x",
+                "Expected a point like \"x,y\""
+            ))
+        );
+        assert!(error("0,9 -> 5,9\n8,0 -> 0,8").is_none());
     }
 
     fn test(
-        test_file: &str,
-        function: &dyn Fn(&[String], bool) -> usize,
+        name: &str,
+        function: &dyn Fn(&[Segment], bool) -> usize,
         allow_diagonals: bool,
         expected: usize,
     ) -> Result<()> {
-        let input = crate::files::read_lines(test_file)?;
+        let input = super::parse_segments(&crate::input::read(name)?)?;
         let result = function(&input, allow_diagonals);
         assert_eq!(result, expected);
         Ok(())
@@ -2,7 +2,8 @@ use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
 
-#[allow(dead_code)]
+use crate::solution::Solution;
+
 fn num_lantern_fish(input: &[u32], days: u64) -> Result<u64> {
     let mut fishes = HashMap::from([
         (0, 0),
@@ -33,32 +34,54 @@ fn num_lantern_fish(input: &[u32], days: u64) -> Result<u64> {
     Ok(total_fish)
 }
 
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u32 = 6;
+
+    type Input = Vec<u32>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(crate::files::parse_numbers_one_line_strict(input)?)
+    }
+
+    fn part_1(input: Self::Input) -> Result<Self::Answer1> {
+        num_lantern_fish(&input, 80)
+    }
+
+    fn part_2(input: Self::Input) -> Result<Self::Answer2> {
+        num_lantern_fish(&input, 256)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
 
     #[test]
     fn part_1_test() -> Result<()> {
-        test("inputs/day6-test.txt", 80, 5934)
+        test("day6-test", 80, 5934)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day6.txt", 80, 352151)
+        test("day6", 80, 352151)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test("inputs/day6-test.txt", 256, 26984457539)
+        test("day6-test", 256, 26984457539)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test("inputs/day6.txt", 256, 1601616884019)
+        test("day6", 256, 1601616884019)
     }
 
-    fn test(test_file: &str, days: u64, expected: u64) -> Result<()> {
-        let input = crate::files::read_numbers_one_line(test_file)?;
+    fn test(name: &str, days: u64, expected: u64) -> Result<()> {
+        let input = crate::files::parse_numbers_one_line(&crate::input::read(name)?);
         assert_eq!(super::num_lantern_fish(&input, days)?, expected);
         Ok(())
     }
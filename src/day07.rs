@@ -2,7 +2,8 @@ use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
 
-#[allow(dead_code)]
+use crate::solution::Solution;
+
 fn min_fuel(input: &[u32], fuel_cost_fn: &dyn Fn(u32, u32) -> u32) -> Result<u32> {
     let mut positions = HashMap::new();
     let max_position = *input.iter().max().ok_or(anyhow!("Empty input"))?;
@@ -19,43 +20,63 @@ fn min_fuel(input: &[u32], fuel_cost_fn: &dyn Fn(u32, u32) -> u32) -> Result<u32
         .ok_or(anyhow!("Empty positions"))
 }
 
-#[allow(dead_code)]
 fn fuel_cost_simple(position: u32, crab: u32) -> u32 {
     i32::abs(crab as i32 - position as i32) as u32
 }
 
-#[allow(dead_code)]
 fn fuel_cost_complex(position: u32, crab: u32) -> u32 {
     let n = i32::abs(crab as i32 - position as i32) as u32;
     n * (n + 1) / 2
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u32 = 7;
+
+    type Input = Vec<u32>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(crate::files::parse_numbers_one_line_strict(input)?)
+    }
+
+    fn part_1(input: Self::Input) -> Result<Self::Answer1> {
+        min_fuel(&input, &fuel_cost_simple)
+    }
+
+    fn part_2(input: Self::Input) -> Result<Self::Answer2> {
+        min_fuel(&input, &fuel_cost_complex)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
 
     #[test]
     fn part_1_test() -> Result<()> {
-        test("inputs/day7-test.txt", &super::fuel_cost_simple, 37)
+        test("day7-test", &super::fuel_cost_simple, 37)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day7.txt", &super::fuel_cost_simple, 356179)
+        test("day7", &super::fuel_cost_simple, 356179)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test("inputs/day7-test.txt", &super::fuel_cost_complex, 168)
+        test("day7-test", &super::fuel_cost_complex, 168)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test("inputs/day7.txt", &super::fuel_cost_complex, 99788435)
+        test("day7", &super::fuel_cost_complex, 99788435)
     }
 
-    fn test(test_file: &str, function: &dyn Fn(u32, u32) -> u32, expected: u32) -> Result<()> {
-        let input = crate::files::read_numbers_one_line(test_file)?;
+    fn test(name: &str, function: &dyn Fn(u32, u32) -> u32, expected: u32) -> Result<()> {
+        let input = crate::files::parse_numbers_one_line(&crate::input::read(name)?);
         assert_eq!(super::min_fuel(&input, function)?, expected);
         Ok(())
     }
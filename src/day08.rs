@@ -1,37 +1,93 @@
 use std::collections::HashSet;
 
-#[allow(dead_code)]
-fn count_1478s(input: &[String]) -> u32 {
+use anyhow::{anyhow, Result};
+
+use crate::{files::InputError, solution::Solution};
+
+/// One line of the notes: the ten unique patterns and the four output digits.
+pub struct Entry {
+    signals: Vec<String>,
+    output: Vec<String>,
+}
+
+fn parse_entries(input: &str) -> Result<Vec<Entry>, InputError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_entry(i + 1, line))
+        .collect()
+}
+
+fn parse_entry(number: usize, line: &str) -> Result<Entry, InputError> {
+    if let Some(column) = line.find(|c: char| !matches!(c, 'a'..='g' | ' ' | '|')) {
+        let text = line[column..].chars().take(1).collect::<String>();
+        return Err(InputError::new(
+            number,
+            column + 1,
+            &text,
+            "Unexpected character",
+        ));
+    }
+    let (signals, output) = line
+        .split_once('|')
+        .ok_or_else(|| InputError::new(number, 1, line, "Expected \"patterns | digits\""))?;
+    let words = |part: &str| {
+        part.split_ascii_whitespace()
+            .map(|word| word.to_owned())
+            .collect::<Vec<String>>()
+    };
+    let (signals_words, output_words) = (words(signals), words(output));
+    if signals_words.len() != 10 {
+        return Err(InputError::new(
+            number,
+            1,
+            signals.trim(),
+            "Expected 10 patterns",
+        ));
+    }
+    if output_words.len() != 4 {
+        let column = line.len() - output.trim_start().len() + 1;
+        return Err(InputError::new(
+            number,
+            column,
+            output.trim(),
+            "Expected 4 digits",
+        ));
+    }
+    Ok(Entry {
+        signals: signals_words,
+        output: output_words,
+    })
+}
+
+fn count_1478s(input: &[Entry]) -> u32 {
     input
         .iter()
-        .filter_map(|s| {
-            let mut parts = s.split('|');
-            parts.next();
-            parts.next()
-        })
-        .flat_map(|s| s.split_ascii_whitespace())
+        .flat_map(|entry| &entry.output)
         // Length scorrespond to 1, 7, 4, 8
         .filter(|s| matches!(s.len(), 2 | 3 | 4 | 7))
         .count() as u32
 }
 
-#[allow(dead_code)]
-fn decode_book(input: &[String]) -> u32 {
-    input.iter().filter_map(|line| decode_line(line)).sum()
+fn decode_book(input: &[Entry]) -> Result<u32> {
+    input
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| decode_line(entry).ok_or(anyhow!("Can't decode line {}", i + 1)))
+        .sum()
 }
 
-fn decode_line(line: &str) -> Option<u32> {
-    let mut parts = line.split('|');
-    let signals = parts.next()?;
-    let one = Digit::new(signals.split_ascii_whitespace().find(|s| s.len() == 2)?);
-    let four = Digit::new(signals.split_ascii_whitespace().find(|s| s.len() == 4)?);
-    let output = parts.next()?;
-    let digit_values: Vec<u32> = output
-        .split_ascii_whitespace()
-        .map(Digit::new)
+// None when the patterns aren't a consistent wiring of the seven segments.
+fn decode_line(entry: &Entry) -> Option<u32> {
+    let one = Digit::new(entry.signals.iter().find(|s| s.len() == 2)?);
+    let four = Digit::new(entry.signals.iter().find(|s| s.len() == 4)?);
+    let digit_values: Vec<u32> = entry
+        .output
+        .iter()
+        .map(|s| Digit::new(s))
         .map(|d| d.decode(&one, &four))
         .rev()
-        .collect();
+        .collect::<Option<Vec<u32>>>()?;
     let mut result = 0;
     for (i, value) in digit_values.iter().enumerate() {
         result += value * u32::pow(10, i as u32);
@@ -70,68 +126,135 @@ impl Digit {
         8 = 7 (ez)
         9 = 6 (6 elements, contains 1, contains 4)
     */
-    fn decode(&self, one: &Digit, four: &Digit) -> u32 {
+    fn decode(&self, one: &Digit, four: &Digit) -> Option<u32> {
         match self.letter_set.len() {
-            2 => 1,
-            3 => 7,
-            4 => 4,
+            2 => Some(1),
+            3 => Some(7),
+            4 => Some(4),
             // 2, 3, 5
             5 => {
                 if self.contains_fully(one) {
-                    3
+                    Some(3)
                 } else if self.intersection(four) == 2 {
-                    2
+                    Some(2)
                 } else if self.intersection(four) == 3 {
-                    5
+                    Some(5)
                 } else {
-                    unreachable!("invalid set of letters")
+                    None
                 }
             }
             // 0, 6 or 9
             6 => {
                 if !self.contains_fully(one) {
-                    6
-                } else if self.contains_fully(one) && !self.contains_fully(four) {
-                    0
-                } else if self.contains_fully(one) && self.contains_fully(four) {
-                    9
+                    Some(6)
+                } else if !self.contains_fully(four) {
+                    Some(0)
                 } else {
-                    unreachable!("invalid set of letters")
+                    Some(9)
                 }
             }
-            7 => 8,
-            _ => unreachable!("invalid number of letters"),
+            7 => Some(8),
+            _ => None,
         }
     }
 }
 
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u32 = 8;
+
+    type Input = Vec<Entry>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_entries(input)?)
+    }
+
+    fn part_1(input: Self::Input) -> Result<Self::Answer1> {
+        Ok(count_1478s(&input))
+    }
+
+    fn part_2(input: Self::Input) -> Result<Self::Answer2> {
+        decode_book(&input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
 
+    use super::Entry;
+    use crate::files::InputError;
+
     #[test]
     fn part_1_test() -> Result<()> {
-        test("inputs/day8-test.txt", &super::count_1478s, 26)
+        test("day8-test", &|input| Ok(super::count_1478s(input)), 26)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day8.txt", &super::count_1478s, 519)
+        test("day8", &|input| Ok(super::count_1478s(input)), 519)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test("inputs/day8-test.txt", &super::decode_book, 61229)
+        test("day8-test", &super::decode_book, 61229)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test("inputs/day8.txt", &super::decode_book, 1027483)
+        test("day8", &super::decode_book, 1027483)
+    }
+
+    #[test]
+    fn parse_errors() {
+        let signals = "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb";
+        let error = |input: &str| super::parse_entries(input).err();
+        assert_eq!(
+            error(&format!("{} | fdgacbe cefdb cefbgd", signals)),
+            Some(InputError::new(
+                1,
+                62,
+                "fdgacbe cefdb cefbgd",
+                "Expected 4 digits"
+            ))
+        );
+        assert_eq!(
+            error("be cfbegad | fdgacbe cefdb cefbgd gcbe"),
+            Some(InputError::new(1, 1, "be cfbegad", "Expected 10 patterns"))
+        );
+        assert_eq!(
+            error(&format!(
+                "{}\n{} fdgacbe cefdb cefbgd gcbe",
+                signals, signals
+            )),
+            Some(InputError::new(
+                1,
+                1,
+                signals,
+                "Expected \"patterns | digits\""
+            ))
+        );
+        assert_eq!(
+            error(&format!("{} | fdgacbe cefdb cefbgd gcbx", signals)),
+            Some(InputError::new(1, 86, "x", "Unexpected character"))
+        );
+    }
+
+    #[test]
+    fn decode_errors() -> Result<()> {
+        // A five segment digit without 1's segments but all of 4's isn't a digit.
+        let input = "ef abcd abcde abcdf abdef abcdef abcdeg abdefg abcdefg abc | abcdg ef ef ef";
+        let entries = super::parse_entries(input)?;
+        assert!(super::decode_book(&entries).is_err());
+        Ok(())
     }
 
-    fn test(test_file: &str, function: &dyn Fn(&[String]) -> u32, expected: u32) -> Result<()> {
-        let input = crate::files::read_lines(test_file)?;
-        assert_eq!(function(&input), expected);
+    fn test(name: &str, function: &dyn Fn(&[Entry]) -> Result<u32>, expected: u32) -> Result<()> {
+        let input = super::parse_entries(&crate::input::read(name)?)?;
+        assert_eq!(function(&input)?, expected);
         Ok(())
     }
 }
@@ -1,9 +1,12 @@
-use std::collections::{HashSet, VecDeque};
+use crate::{
+    basins::Basins,
+    matrix::{Matrix, Point},
+    solution::Solution,
+};
 
-use crate::matrix::{Matrix, Point};
+use anyhow::Result;
 
 impl Matrix {
-    #[allow(dead_code)]
     fn low_point_value_sum(&self) -> u32 {
         self.all_points()
             .filter(|p| self.is_low_point(p))
@@ -25,33 +28,35 @@ impl Matrix {
         }
     }
 
-    #[allow(dead_code)]
     fn top_basin_sizes_product(&self) -> u32 {
-        let mut visited = HashSet::new();
-        let mut q = VecDeque::new();
-        let mut result = Vec::new();
-
-        for point in self.all_points() {
-            let mut basin_size = 0;
-            q.push_back(point);
-            while let Some(point) = q.pop_front() {
-                if *self.value(&point).unwrap() == 9 || visited.contains(&point) {
-                    visited.insert(point);
-                    continue;
-                }
-
-                visited.insert(point);
-                basin_size += 1;
-
-                for neighbour in self.neighbours(point) {
-                    q.push_back(neighbour);
-                }
-            }
-            result.push(basin_size)
-        }
+        Basins::new(self, |value| value == 9)
+            .by_size()
+            .iter()
+            .take(3)
+            .map(|basin| basin.size() as u32)
+            .product()
+    }
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u32 = 9;
+
+    type Input = Matrix;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Matrix::new(input)
+    }
+
+    fn part_1(matrix: Self::Input) -> Result<Self::Answer1> {
+        Ok(matrix.low_point_value_sum())
+    }
 
-        result.sort_by(|a, b| b.cmp(a));
-        result.iter().take(3).product()
+    fn part_2(matrix: Self::Input) -> Result<Self::Answer2> {
+        Ok(matrix.top_basin_sizes_product())
     }
 }
 
@@ -63,30 +68,26 @@ mod tests {
 
     #[test]
     fn part_1_test() -> Result<()> {
-        test("inputs/day9-test.txt", &Matrix::low_point_value_sum, 15)
+        test("day9-test", &Matrix::low_point_value_sum, 15)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day9.txt", &Matrix::low_point_value_sum, 528)
+        test("day9", &Matrix::low_point_value_sum, 528)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test(
-            "inputs/day9-test.txt",
-            &Matrix::top_basin_sizes_product,
-            1134,
-        )
+        test("day9-test", &Matrix::top_basin_sizes_product, 1134)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test("inputs/day9.txt", &Matrix::top_basin_sizes_product, 920448)
+        test("day9", &Matrix::top_basin_sizes_product, 920448)
     }
 
-    fn test(test_file: &str, function: &dyn Fn(&Matrix) -> u32, expected: u32) -> Result<()> {
-        let input = crate::files::read_string(test_file)?;
+    fn test(name: &str, function: &dyn Fn(&Matrix) -> u32, expected: u32) -> Result<()> {
+        let input = crate::input::read(name)?;
         let matrix = super::Matrix::new(&input)?;
         assert_eq!(function(&matrix), expected);
         Ok(())
@@ -1,7 +1,27 @@
+use anyhow::{anyhow, Result};
+
+use crate::{files::InputError, solution::Solution};
+
 // First part - got it right on the first compilation!
 // Second part - forgot scores.sort(), and had to replace u32 with u64 (overflow)
 
-#[allow(dead_code)]
+// Every character is checked here, so the scoring below only ever sees brackets.
+fn parse_lines(input: &str) -> Result<Vec<String>, InputError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| match line.find(|c| !"(){}<>[]".contains(c)) {
+            Some(column) => Err(InputError::new(
+                i + 1,
+                line[..column].chars().count() + 1,
+                &line[column..].chars().take(1).collect::<String>(),
+                "Unexpected character",
+            )),
+            None => Ok(line.to_owned()),
+        })
+        .collect()
+}
+
 fn sum_corruption_points(input: &[String]) -> u64 {
     input.iter().map(|line| corruption_points(line)).sum()
 }
@@ -43,8 +63,7 @@ fn char_corruption_points(c: char) -> u64 {
     }
 }
 
-#[allow(dead_code)]
-fn median_completion_points(input: &[String]) -> u64 {
+fn median_completion_points(input: &[String]) -> Result<u64> {
     let mut scores: Vec<u64> = input
         .iter()
         .filter(|line| corruption_points(line) == 0)
@@ -55,7 +74,10 @@ fn median_completion_points(input: &[String]) -> u64 {
     // observable difference for this data type
     scores.sort_unstable();
 
-    scores[scores.len() / 2]
+    scores
+        .get(scores.len() / 2)
+        .copied()
+        .ok_or(anyhow!("No incomplete lines"))
 }
 
 fn completion_points(line: &str) -> u64 {
@@ -96,45 +118,74 @@ fn char_completion_points(c: char) -> u64 {
     }
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+
+    type Input = Vec<String>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input)?)
+    }
+
+    fn part_1(input: Self::Input) -> Result<Self::Answer1> {
+        Ok(sum_corruption_points(&input))
+    }
+
+    fn part_2(input: Self::Input) -> Result<Self::Answer2> {
+        median_completion_points(&input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
 
+    use crate::files::InputError;
+
     #[test]
     fn part_1_test() -> Result<()> {
         test(
-            "inputs/day10-test.txt",
-            &super::sum_corruption_points,
+            "day10-test",
+            &|input| Ok(super::sum_corruption_points(input)),
             26397,
         )
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day10.txt", &super::sum_corruption_points, 290691)
+        test(
+            "day10",
+            &|input| Ok(super::sum_corruption_points(input)),
+            290691,
+        )
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test(
-            "inputs/day10-test.txt",
-            &super::median_completion_points,
-            288957,
-        )
+        test("day10-test", &super::median_completion_points, 288957)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test(
-            "inputs/day10.txt",
-            &super::median_completion_points,
-            2768166558,
-        )
+        test("day10", &super::median_completion_points, 2768166558)
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            super::parse_lines("()\n(ab)").err(),
+            Some(InputError::new(2, 2, "a", "Unexpected character"))
+        );
+        assert!(super::median_completion_points(&["(]".to_owned()]).is_err());
     }
 
-    fn test(test_file: &str, function: &dyn Fn(&[String]) -> u64, expected: u64) -> Result<()> {
-        let input = crate::files::read_lines(test_file)?;
-        assert_eq!(function(&input), expected);
+    fn test(name: &str, function: &dyn Fn(&[String]) -> Result<u64>, expected: u64) -> Result<()> {
+        let input = super::parse_lines(&crate::input::read(name)?)?;
+        assert_eq!(function(&input)?, expected);
         Ok(())
     }
 }
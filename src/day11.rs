@@ -1,82 +1,122 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
 
-use crate::matrix::{Matrix, Point};
+use crate::{
+    automaton::Automaton,
+    matrix::{Grid, Matrix, Point},
+    neighbourhood::{Offset, Topology, MOORE},
+    solution::Solution,
+};
 
-impl Matrix {
-    #[allow(dead_code)]
-    fn total_octopus_flashes(&mut self) -> u32 {
-        let generations = 100;
-        let mut result = 0;
-        let mut q = VecDeque::new();
+use anyhow::Result;
 
-        for _ in 0..generations {
-            let mut clear_q = HashSet::new();
-            self.add_to_all(1);
-            self.find(10).for_each(|flasher| q.push_back(flasher));
-            while let Some(flasher) = q.pop_front() {
-                result += 1;
-                clear_q.insert(flasher);
-
-                for neighbour in self
-                    .neighbours_with_diagonals(flasher)
-                    .collect::<Vec<Point>>()
-                {
-                    if let Some(val) = self.value(&neighbour).copied() {
-                        if val <= 9 {
-                            self.add(&neighbour, 1);
-                        }
-                        if val == 9 && !clear_q.contains(&neighbour) {
-                            q.push_back(neighbour);
-                        }
-                    }
-                }
-            }
+pub struct Octopuses {
+    energy: Matrix,
+    stencil: &'static [Offset],
+    topology: Topology,
+}
 
-            for point in &clear_q {
-                self.set(point, 0);
-            }
+/// What happened in one step.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Flashes {
+    pub flashed: Vec<Point>,
+}
+
+impl Octopuses {
+    fn new(energy: Matrix) -> Octopuses {
+        Octopuses::with_neighbourhood(energy, MOORE, Topology::Bounded)
+    }
+
+    fn with_neighbourhood(
+        energy: Matrix,
+        stencil: &'static [Offset],
+        topology: Topology,
+    ) -> Octopuses {
+        Octopuses {
+            energy,
+            stencil,
+            topology,
         }
+    }
 
-        result
+    fn len(&self) -> usize {
+        self.energy.len()
     }
+}
 
-    #[allow(dead_code)]
-    fn first_synchronized_flash(&mut self) -> u32 {
+impl Automaton for Octopuses {
+    type Report = Flashes;
+
+    fn step(&mut self) -> Flashes {
+        let mut flashed = Grid::filled(self.energy.max_x, self.energy.max_y, false);
         let mut q = VecDeque::new();
 
-        for generation in 1.. {
-            let mut clear_q = HashSet::new();
-            self.add_to_all(1);
-            self.find(10).for_each(|flasher| q.push_back(flasher));
-            while let Some(flasher) = q.pop_front() {
-                clear_q.insert(flasher);
-
-                for neighbour in self
-                    .neighbours_with_diagonals(flasher)
-                    .collect::<Vec<Point>>()
-                {
-                    if let Some(val) = self.value(&neighbour).copied() {
-                        if val <= 9 {
-                            self.add(&neighbour, 1);
-                        }
-                        if val == 9 && !clear_q.contains(&neighbour) {
-                            q.push_back(neighbour);
-                        }
-                    }
-                }
+        self.energy.add_to_all(1);
+        self.energy
+            .find(10)
+            .for_each(|flasher| q.push_back(flasher));
+        while let Some(flasher) = q.pop_front() {
+            if flashed.value(&flasher) == Some(&true) {
+                continue;
             }
-
-            for point in &clear_q {
-                self.set(point, 0);
+            flashed.set(&flasher, true);
+
+            for neighbour in self
+                .energy
+                .neighbours_in(flasher, self.stencil, self.topology)
+                .collect::<Vec<Point>>()
+            {
+                let val = *self.energy.value(&neighbour).unwrap();
+                if val <= 9 {
+                    self.energy.add(&neighbour, 1);
+                }
+                if val == 9 {
+                    q.push_back(neighbour);
+                }
             }
+        }
 
-            // All elements have flashed
-            if clear_q.len() == self.len() {
-                return generation;
-            }
+        let flashed = flashed.find(true).collect::<Vec<Point>>();
+        for point in &flashed {
+            self.energy.set(point, 0);
         }
+        Flashes { flashed }
+    }
+}
+
+fn total_octopus_flashes(mut octopuses: Octopuses) -> usize {
+    octopuses
+        .generations()
+        .take(100)
+        .map(|flashes| flashes.flashed.len())
+        .sum()
+}
 
-        unreachable!("Loop must end")
+fn first_synchronized_flash(mut octopuses: Octopuses) -> usize {
+    let size = octopuses.len();
+    // All elements have flashed
+    let (generation, _) = octopuses.run_until(|flashes| flashes.flashed.len() == size);
+    generation
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+
+    type Input = Octopuses;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Octopuses::new(Matrix::new(input)?))
+    }
+
+    fn part_1(octopuses: Self::Input) -> Result<Self::Answer1> {
+        Ok(total_octopus_flashes(octopuses))
+    }
+
+    fn part_2(octopuses: Self::Input) -> Result<Self::Answer2> {
+        Ok(first_synchronized_flash(octopuses))
     }
 }
 
@@ -84,40 +124,94 @@ impl Matrix {
 mod tests {
     use anyhow::Result;
 
-    use crate::matrix::Matrix;
+    use super::Octopuses;
+    use crate::{
+        automaton::Automaton,
+        cycle::{self, Cycle},
+        matrix::Matrix,
+        neighbourhood::{Topology, VON_NEUMANN},
+    };
 
     #[test]
     fn part_1_test() -> Result<()> {
-        test(
-            "inputs/day11-test.txt",
-            &Matrix::total_octopus_flashes,
-            1656,
-        )
+        test("day11-test", &super::total_octopus_flashes, 1656)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day11.txt", &Matrix::total_octopus_flashes, 1627)
+        test("day11", &super::total_octopus_flashes, 1627)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test(
-            "inputs/day11-test.txt",
-            &Matrix::first_synchronized_flash,
-            195,
-        )
+        test("day11-test", &super::first_synchronized_flash, 195)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test("inputs/day11.txt", &Matrix::first_synchronized_flash, 329)
+        test("day11", &super::first_synchronized_flash, 329)
+    }
+
+    #[test]
+    fn synchronized_period() -> Result<()> {
+        let input = crate::input::read("day11-test")?;
+        let mut octopuses = Octopuses::new(Matrix::new(&input)?);
+        let size = octopuses.len();
+        octopuses.run_until(|flashes| flashes.flashed.len() == size);
+        // Once every octopus is at 0 they all reach 10 together again.
+        let (period, _) = octopuses.run_until(|flashes| flashes.flashed.len() == size);
+        assert_eq!(period, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn cycle() -> Result<()> {
+        let input = crate::input::read("day11-test")?;
+        let octopuses = Octopuses::new(Matrix::new(&input)?);
+        let step = |energy: &Matrix| {
+            let mut next = Octopuses::new(energy.clone());
+            next.step();
+            next.energy
+        };
+        let cycle = cycle::hash_history(octopuses.energy.clone(), step, 1000).unwrap();
+        // Everything flashes together on step 195, then every 10 steps after.
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 195,
+                period: 10
+            }
+        );
+
+        let far = cycle::state_at(octopuses.energy.clone(), step, 1_000_000);
+        let mut simulated = Octopuses::new(octopuses.energy);
+        simulated
+            .generations()
+            .take(cycle.earliest_equivalent(1_000_000))
+            .for_each(drop);
+        assert_eq!(far, simulated.energy);
+        Ok(())
+    }
+
+    #[test]
+    fn other_neighbourhoods() -> Result<()> {
+        let matrix = Matrix::new("90\n00")?;
+        let mut bounded =
+            Octopuses::with_neighbourhood(matrix.clone(), VON_NEUMANN, Topology::Bounded);
+        assert_eq!(bounded.step().flashed.len(), 1);
+        assert_eq!(bounded.energy, Matrix::new("02\n21")?);
+
+        // On a 2x2 torus the flasher's right and down neighbours are also its left and up ones.
+        let mut toroidal = Octopuses::with_neighbourhood(matrix, VON_NEUMANN, Topology::Toroidal);
+        assert_eq!(toroidal.step().flashed.len(), 1);
+        assert_eq!(toroidal.energy, Matrix::new("03\n31")?);
+        Ok(())
     }
 
-    fn test(test_file: &str, function: &dyn Fn(&mut Matrix) -> u32, expected: u32) -> Result<()> {
-        let input = crate::files::read_string(test_file)?;
-        let mut matrix = Matrix::new(&input)?;
-        assert_eq!(function(&mut matrix), expected);
+    fn test(name: &str, function: &dyn Fn(Octopuses) -> usize, expected: usize) -> Result<()> {
+        let input = crate::input::read(name)?;
+        let octopuses = Octopuses::new(Matrix::new(&input)?);
+        assert_eq!(function(octopuses), expected);
         Ok(())
     }
 }
@@ -1,22 +1,17 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    fmt::Write,
-};
+use std::collections::{HashMap, HashSet};
 
 use anyhow::{anyhow, Result};
 
-struct Graph {
+use crate::{search, solution::Solution};
+
+pub struct Graph {
     nodes: HashMap<Node, HashSet<Node>>,
 }
 
-#[derive(PartialEq, Eq, Clone, Hash)]
-struct Node(String);
-
-#[derive(PartialEq, Eq, Clone)]
-struct Path(Vec<Node>);
+#[derive(PartialEq, Eq, Clone, Hash, Debug)]
+pub struct Node(String);
 
 impl Graph {
-    #[allow(dead_code)]
     fn new(input: &[String]) -> Result<Graph> {
         let mut graph = Graph {
             nodes: HashMap::new(),
@@ -45,78 +40,45 @@ impl Graph {
         );
         self.nodes
             .entry(one.clone())
-            .or_insert_with(HashSet::new)
+            .or_default()
             .insert(two.clone());
-        self.nodes
-            .entry(two)
-            .or_insert_with(HashSet::new)
-            .insert(one);
+        self.nodes.entry(two).or_default().insert(one);
         Ok(())
     }
 
-    fn get_small_connections(&self, node: &Node) -> impl Iterator<Item = &Node> {
-        self.nodes
-            .get(node)
-            .unwrap()
-            .iter()
-            .filter(|node| node.is_small())
-    }
-
-    fn get_large_connections(&self, node: &Node) -> impl Iterator<Item = &Node> {
-        self.nodes
-            .get(node)
-            .unwrap()
-            .iter()
-            .filter(|node| !node.is_small())
+    /// Adapter for `search`: the caves connected to `node`.
+    pub fn connections<'a>(&'a self, node: &Node) -> impl Iterator<Item = &'a Node> {
+        self.nodes.get(node).into_iter().flatten()
     }
 
-    #[allow(dead_code)]
     fn part_one(&self) -> usize {
-        let skip_small_connection = |path: &Path, node: &Node| path.contains_node(node);
+        let skip_small_connection = |path: &[&Node], node: &Node| path.contains(&node);
         self.num_possible_paths(&skip_small_connection)
     }
 
-    #[allow(dead_code)]
     fn part_two(&self) -> usize {
-        let skip_small_connection = |path: &Path, node: &Node| {
-            (path.contains_any_double_small_node() && path.contains_node(node)) || node.0 == "start"
+        let skip_small_connection = |path: &[&Node], node: &Node| {
+            (contains_any_double_small_node(path) && path.contains(&node)) || node.0 == "start"
         };
         self.num_possible_paths(&skip_small_connection)
     }
 
-    fn num_possible_paths(&self, skip_small_connection: &dyn Fn(&Path, &Node) -> bool) -> usize {
-        let mut start = Path::new();
-        start.add_node(&Node("start".to_owned()));
-
-        let end = Node("end".to_owned());
-
-        let mut result = HashSet::new();
-        let mut q: VecDeque<Path> = VecDeque::new();
-        q.push_back(start);
-        while let Some(path) = q.pop_front() {
-            let last_node = path.last_node();
-            // path is complete
-            if last_node == &end {
-                result.insert(path.to_string());
-                continue;
-            }
-
-            for connection in self.get_large_connections(last_node) {
-                let mut new_path = path.clone();
-                new_path.add_node(connection);
-                q.push_back(new_path);
-            }
-
-            for connection in self.get_small_connections(last_node) {
-                if skip_small_connection(&path, connection) {
-                    continue;
-                }
-                let mut new_path = path.clone();
-                new_path.add_node(connection);
-                q.push_back(new_path);
-            }
-        }
-        result.len()
+    fn num_possible_paths(&self, skip_small_connection: &dyn Fn(&[&Node], &Node) -> bool) -> usize {
+        let start = Node("start".to_owned());
+        let start = self.nodes.get_key_value(&start).map(|(node, _)| node);
+        let start = match start {
+            Some(start) => start,
+            None => return 0,
+        };
+
+        let extend = |path: &[&Node]| {
+            self.connections(path[path.len() - 1])
+                .filter(|connection| {
+                    !connection.is_small() || !skip_small_connection(path, connection)
+                })
+                .collect::<Vec<&Node>>()
+        };
+        search::all_paths(start, extend, |node| node.0 == "end").len()
     }
 }
 
@@ -126,43 +88,35 @@ impl Node {
     }
 }
 
-impl Path {
-    fn new() -> Path {
-        Path(Vec::new())
-    }
+fn contains_any_double_small_node(path: &[&Node]) -> bool {
+    let small_nodes = path
+        .iter()
+        .filter(|node| node.is_small())
+        .collect::<Vec<_>>();
+    let small_nodes_set = small_nodes.iter().collect::<HashSet<_>>();
 
-    fn last_node(&self) -> &Node {
-        &self.0[self.0.len() - 1]
-    }
+    small_nodes.len() > small_nodes_set.len()
+}
 
-    fn add_node(&mut self, node: &Node) {
-        self.0.push(node.clone())
-    }
+pub struct Day12;
 
-    // O(N) for now. Can optimise if necessary.
-    fn contains_node(&self, node: &Node) -> bool {
-        self.0.iter().any(|n| n == node)
-    }
+impl Solution for Day12 {
+    const DAY: u32 = 12;
 
-    fn contains_any_double_small_node(&self) -> bool {
-        let small_nodes = self
-            .0
-            .iter()
-            .filter(|node| node.is_small())
-            .collect::<Vec<_>>();
-        let small_nodes_set = small_nodes.iter().collect::<HashSet<_>>();
+    type Input = Graph;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-        small_nodes.len() > small_nodes_set.len()
+    fn parse(input: &str) -> Result<Self::Input> {
+        Graph::new(&crate::files::parse_lines(input))
     }
-}
 
-impl std::fmt::Display for Path {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for s in &self.0 {
-            f.write_str(&s.0)?;
-            f.write_char('-')?;
-        }
-        Ok(())
+    fn part_1(graph: Self::Input) -> Result<Self::Answer1> {
+        Ok(graph.part_one())
+    }
+
+    fn part_2(graph: Self::Input) -> Result<Self::Answer2> {
+        Ok(graph.part_two())
     }
 }
 
@@ -170,31 +124,45 @@ impl std::fmt::Display for Path {
 mod tests {
     use anyhow::Result;
 
-    use super::Graph;
+    use super::{Graph, Node};
 
     #[test]
     fn part_1_test() -> Result<()> {
-        test("inputs/day12-test.txt", &Graph::part_one, 19)
+        test("day12-test", &Graph::part_one, 19)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day12.txt", &Graph::part_one, 3463)
+        test("day12", &Graph::part_one, 3463)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test("inputs/day12-test.txt", &Graph::part_two, 103)
+        test("day12-test", &Graph::part_two, 103)
     }
 
     #[test]
     #[ignore]
     fn part_2_real() -> Result<()> {
-        test("inputs/day12.txt", &Graph::part_two, 91533)
+        test("day12", &Graph::part_two, 91533)
+    }
+
+    #[test]
+    fn shortest_route() -> Result<()> {
+        let input = crate::files::parse_lines(&crate::input::read("day12-test")?);
+        let graph = Graph::new(&input)?;
+        let start = Node("start".to_owned());
+        let path = crate::search::bfs(
+            &start,
+            |node| graph.connections(node),
+            |node| node.0 == "end",
+        );
+        assert_eq!(path.map(|path| path.len()), Some(3));
+        Ok(())
     }
 
-    fn test(test_file: &str, function: &dyn Fn(&Graph) -> usize, expected: usize) -> Result<()> {
-        let input = crate::files::read_lines(test_file)?;
+    fn test(name: &str, function: &dyn Fn(&Graph) -> usize, expected: usize) -> Result<()> {
+        let input = crate::files::parse_lines(&crate::input::read(name)?);
         let graph = super::Graph::new(&input)?;
         assert_eq!(function(&graph), expected);
         Ok(())
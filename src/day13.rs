@@ -1,78 +1,92 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fmt::Write,
-};
+use std::collections::VecDeque;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-use crate::matrix::Point;
+use crate::{
+    files::InputError,
+    solution::Solution,
+    sparse::{Point, SparseGrid},
+};
 
 // Almost got it right the first time. 2 minor mistakes for the first part
 // Passed the wrong test input
 // Parsed the folds wrong
 // Took 2 tries to get the printing logic right
 
-struct Paper {
-    points: HashSet<Point>,
+pub struct Paper {
+    dots: SparseGrid<bool>,
     folds: VecDeque<Fold>,
 }
 
 enum Fold {
-    X(usize),
-    Y(usize),
+    X(i32),
+    Y(i32),
 }
 
 impl Fold {
-    fn new(s: &str) -> Option<Fold> {
-        let mut parts = s.split('=');
-        parts.next();
-        let index = parts.next()?.parse::<usize>().ok()?;
-        match s.chars().nth(11) {
-            Some('x') => Some(Fold::X(index)),
-            Some('y') => Some(Fold::Y(index)),
-            _ => unreachable!("invalid fold"),
+    const PREFIX: &'static str = "fold along ";
+
+    fn new(number: usize, s: &str) -> Result<Fold, InputError> {
+        let error = |column, text, reason| InputError::new(number, column, text, reason);
+        let instruction = s
+            .strip_prefix(Self::PREFIX)
+            .ok_or_else(|| error(1, s, "Expected \"fold along\""))?;
+        let column = Self::PREFIX.len() + 1;
+        let (axis, index) = instruction
+            .split_once('=')
+            .ok_or_else(|| error(column, instruction, "Expected a fold like \"x=5\""))?;
+        let index_column = column + axis.len() + 1;
+        let index = index
+            .parse::<i32>()
+            .map_err(|_| error(index_column, index, "Expected a number"))?;
+        match axis {
+            "x" => Ok(Fold::X(index)),
+            "y" => Ok(Fold::Y(index)),
+            _ => Err(error(column, axis, "Expected x or y")),
+        }
+    }
+
+    fn apply(&self, point: &Point) -> Point {
+        match self {
+            Fold::X(index) if point.x > *index => Point::new(2 * index - point.x, point.y),
+            Fold::Y(index) if point.y > *index => Point::new(point.x, 2 * index - point.y),
+            _ => *point,
         }
     }
 }
 
 impl Paper {
-    #[allow(dead_code)]
     fn new(input: &str) -> Result<Paper> {
-        let mut parts = input.split("\n\n");
-        let points = parts
-            .next()
-            .ok_or(anyhow::anyhow!("Invalid input"))?
-            .split_ascii_whitespace()
-            .filter_map(Point::new)
-            .collect();
+        let mut lines = input.lines().enumerate().map(|(i, line)| (i + 1, line));
+        let mut dots = SparseGrid::new(false);
+        for (number, line) in lines.by_ref().take_while(|(_, line)| !line.is_empty()) {
+            let point = parse_point(line)
+                .ok_or_else(|| InputError::new(number, 1, line, "Expected a dot like \"x,y\""))?;
+            dots.set(point, true);
+        }
 
-        let folds = parts
-            .next()
-            .ok_or(anyhow::anyhow!("Invalid input"))?
-            .split('\n')
-            .filter_map(Fold::new)
-            .collect();
+        let folds = lines
+            .map(|(number, line)| Fold::new(number, line))
+            .collect::<Result<VecDeque<Fold>, InputError>>()?;
+        if folds.is_empty() {
+            return Err(anyhow!("No folds"));
+        }
 
-        Ok(Paper { points, folds })
+        Ok(Paper { dots, folds })
     }
 
-    #[allow(dead_code)]
     fn fold_once(&mut self) -> Result<()> {
-        let fold = self
-            .folds
-            .pop_front()
-            .ok_or(anyhow::anyhow!("no more folds"))?;
+        let fold = self.folds.pop_front().ok_or(anyhow!("no more folds"))?;
 
-        self.points = self
-            .points
-            .iter()
-            .map(|point| point.transpose(&fold))
-            .collect();
+        let mut folded = SparseGrid::new(false);
+        for (point, _) in self.dots.cells() {
+            folded.set(fold.apply(point), true);
+        }
+        self.dots = folded;
 
         Ok(())
     }
 
-    #[allow(dead_code)]
     fn fold_completely(&mut self) -> Result<()> {
         while !self.folds.is_empty() {
             self.fold_once()?;
@@ -80,54 +94,43 @@ impl Paper {
         Ok(())
     }
 
-    #[allow(dead_code)]
     fn num_remaining_points(&self) -> usize {
-        self.points.len()
+        self.dots.values().count()
     }
 }
 
+fn parse_point(s: &str) -> Option<Point> {
+    let (x, y) = s.split_once(',')?;
+    Some(Point::new(x.parse().ok()?, y.parse().ok()?))
+}
+
 impl std::fmt::Display for Paper {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let max_x = self.points.iter().map(|p| p.x).max().unwrap() + 1;
-        let max_y = self.points.iter().map(|p| p.y).max().unwrap() + 1;
-        for y in 0..max_y {
-            for x in 0..max_x {
-                if self.points.contains(&Point { x, y }) {
-                    f.write_char('#')?;
-                } else {
-                    f.write_char('.')?;
-                }
-            }
-            f.write_str("\n")?;
-        }
-        Ok(())
+        f.write_str(&self.dots.render(|dot| if *dot { '#' } else { '.' }))
     }
 }
 
-impl Point {
-    fn transpose(&self, fold: &Fold) -> Point {
-        match fold {
-            Fold::X(index) => {
-                if self.x < *index {
-                    *self
-                } else {
-                    Point {
-                        x: 2 * index - self.x,
-                        y: self.y,
-                    }
-                }
-            }
-            Fold::Y(index) => {
-                if self.y < *index {
-                    *self
-                } else {
-                    Point {
-                        x: self.x,
-                        y: 2 * index - self.y,
-                    }
-                }
-            }
-        }
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+
+    type Input = Paper;
+    type Answer1 = usize;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Paper::new(input)
+    }
+
+    fn part_1(mut paper: Self::Input) -> Result<Self::Answer1> {
+        paper.fold_once()?;
+        Ok(paper.num_remaining_points())
+    }
+
+    fn part_2(mut paper: Self::Input) -> Result<Self::Answer2> {
+        paper.fold_completely()?;
+        Ok(paper.to_string())
     }
 }
 
@@ -136,33 +139,60 @@ mod tests {
     use anyhow::Result;
 
     use super::Paper;
+    use crate::files::InputError;
 
     #[test]
     fn part_1_test() -> Result<()> {
-        test("inputs/day13-test.txt", &Paper::fold_once, 17)
+        test("day13-test", &Paper::fold_once, 17)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day13.txt", &Paper::fold_once, 802)
+        test("day13", &Paper::fold_once, 802)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test("inputs/day13-test.txt", &Paper::fold_completely, 16)
+        test("day13-test", &Paper::fold_completely, 16)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test("inputs/day13.txt", &Paper::fold_completely, 103)
+        test("day13", &Paper::fold_completely, 103)
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |input| {
+            Paper::new(input)
+                .err()
+                .and_then(|e| e.downcast::<InputError>().ok())
+        };
+        assert_eq!(
+            error("6,10\n0;14\n\nfold along y=7"),
+            Some(InputError::new(2, 1, "0;14", "Expected a dot like \"x,y\""))
+        );
+        assert_eq!(
+            error("6,10\n\nfold along z=7"),
+            Some(InputError::new(3, 12, "z", "Expected x or y"))
+        );
+        assert_eq!(
+            error("6,10\n\nfold along y=7\nfold along x=five"),
+            Some(InputError::new(4, 14, "five", "Expected a number"))
+        );
+        assert_eq!(
+            error("6,10\n\nfold y=7"),
+            Some(InputError::new(3, 1, "fold y=7", "Expected \"fold along\""))
+        );
+        assert!(Paper::new("6,10\n").is_err());
     }
 
     fn test(
-        test_file: &str,
+        name: &str,
         function: &dyn Fn(&mut Paper) -> Result<()>,
         expected: usize,
     ) -> Result<()> {
-        let input = crate::files::read_string(test_file)?;
+        let input = crate::input::read(name)?;
         let mut paper = Paper::new(&input)?;
         function(&mut paper)?;
 
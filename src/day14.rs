@@ -2,8 +2,11 @@ use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
 
-struct Polymer {
+use crate::{files::InputError, solution::Solution};
+
+pub struct Polymer {
     template: HashMap<Pair, u64>,
+    // A pair without a rule is left as it is.
     rules: HashMap<Pair, (Pair, Pair)>,
     start: char,
     end: char,
@@ -13,27 +16,27 @@ struct Polymer {
 struct Pair((char, char));
 
 impl Polymer {
-    #[allow(dead_code)]
     fn new(input: &str) -> Result<Polymer> {
-        let mut parts = input.split("\n\n");
-        let template = parts.next().ok_or(anyhow!("Invalid input"))?;
+        let mut lines = input.lines();
+        let template = lines.next().ok_or(anyhow!("Invalid input"))?;
         let start = template.chars().next().ok_or(anyhow!("Invalid input"))?;
         let end = template.chars().last().ok_or(anyhow!("Invalid input"))?;
         let template = template
             .chars()
             .zip(template.chars().skip(1))
-            .map(|p| Pair { 0: p })
+            .map(Pair)
             .fold(HashMap::new(), |mut acc, pair| {
                 *acc.entry(pair).or_default() += 1;
                 acc
             });
 
-        let rules: HashMap<Pair, (Pair, Pair)> = parts
-            .next()
-            .ok_or(anyhow!("Invalid input"))?
-            .split('\n')
-            .filter_map(Pair::new_group)
-            .collect();
+        if lines.next() != Some("") {
+            return Err(anyhow!("Expected a blank line after the template"));
+        }
+        let rules = lines
+            .enumerate()
+            .map(|(i, line)| Pair::new_group(i + 3, line))
+            .collect::<Result<HashMap<Pair, (Pair, Pair)>, InputError>>()?;
 
         Ok(Polymer {
             template,
@@ -43,7 +46,6 @@ impl Polymer {
         })
     }
 
-    #[allow(dead_code)]
     fn pair_insertion_multiple(&mut self, n: u32) {
         for _ in 1..=n {
             self.pair_insertion();
@@ -55,14 +57,17 @@ impl Polymer {
             self.template
                 .iter()
                 .fold(HashMap::new(), |mut acc, (pair, occurrences)| {
-                    let new_pairs = self.rules.get(pair).unwrap();
-                    *acc.entry(new_pairs.0).or_default() += occurrences;
-                    *acc.entry(new_pairs.1).or_default() += occurrences;
+                    match self.rules.get(pair) {
+                        Some(new_pairs) => {
+                            *acc.entry(new_pairs.0).or_default() += occurrences;
+                            *acc.entry(new_pairs.1).or_default() += occurrences;
+                        }
+                        None => *acc.entry(*pair).or_default() += occurrences,
+                    }
                     acc
                 });
     }
 
-    #[allow(dead_code)]
     fn quantity_difference(&self) -> Result<u64> {
         let mut char_counts: HashMap<char, u64> =
             self.template
@@ -86,23 +91,49 @@ impl Polymer {
 }
 
 impl Pair {
-    fn new_group(line: &str) -> Option<(Pair, (Pair, Pair))> {
-        let mut parts = line.split(" -> ");
-
-        let source = Pair::new(parts.next()?.chars())?;
-        let dest = parts.next()?.chars().next()?;
-        let dest_one = Pair {
-            0: (source.0 .0, dest),
+    // A rule like "CH -> B", on line `number`.
+    fn new_group(number: usize, line: &str) -> Result<(Pair, (Pair, Pair)), InputError> {
+        let (source, dest) = line
+            .split_once(" -> ")
+            .ok_or_else(|| InputError::new(number, 1, line, "Expected a rule like \"AB -> C\""))?;
+        let source = match source.chars().collect::<Vec<char>>()[..] {
+            [a, b] => Pair((a, b)),
+            _ => return Err(InputError::new(number, 1, source, "Expected 2 elements")),
         };
-        let dest_two = Pair {
-            0: (dest, source.0 .1),
+        let dest = match dest.chars().collect::<Vec<char>>()[..] {
+            [c] => c,
+            _ => {
+                let column = line.len() - dest.len() + 1;
+                return Err(InputError::new(number, column, dest, "Expected 1 element"));
+            }
         };
-        Some((source, (dest_one, dest_two)))
+        let dest_one = Pair((source.0 .0, dest));
+        let dest_two = Pair((dest, source.0 .1));
+        Ok((source, (dest_one, dest_two)))
+    }
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+
+    type Input = Polymer;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Polymer::new(input)
+    }
+
+    fn part_1(mut polymer: Self::Input) -> Result<Self::Answer1> {
+        polymer.pair_insertion_multiple(10);
+        polymer.quantity_difference()
     }
 
-    fn new(mut c: std::str::Chars) -> Option<Pair> {
-        let p = (c.next()?, c.next()?);
-        Some(Pair { 0: p })
+    fn part_2(mut polymer: Self::Input) -> Result<Self::Answer2> {
+        polymer.pair_insertion_multiple(40);
+        polymer.quantity_difference()
     }
 }
 
@@ -110,28 +141,65 @@ impl Pair {
 mod tests {
     use anyhow::Result;
 
+    use crate::files::InputError;
+
     #[test]
     fn part_1_test() -> Result<()> {
-        test("inputs/day14-test.txt", 10, 1588)
+        test("day14-test", 10, 1588)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day14.txt", 10, 3587)
+        test("day14", 10, 3587)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test("inputs/day14-test.txt", 40, 2188189693529)
+        test("day14-test", 40, 2188189693529)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test("inputs/day14.txt", 40, 3906445077999)
+        test("day14", 40, 3906445077999)
+    }
+
+    #[test]
+    fn missing_rules() -> Result<()> {
+        let mut polymer = super::Polymer::new("NNB\n\nNN -> C")?;
+        polymer.pair_insertion_multiple(1);
+        // NCNB
+        assert_eq!(polymer.quantity_difference()?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |input| {
+            super::Polymer::new(input)
+                .err()
+                .and_then(|e| e.downcast::<InputError>().ok())
+        };
+        assert_eq!(
+            error("NN\n\nNN -> C\nNC => B"),
+            Some(InputError::new(
+                4,
+                1,
+                "NC => B",
+                "Expected a rule like \"AB -> C\""
+            ))
+        );
+        assert_eq!(
+            error("NN\n\nNNN -> C"),
+            Some(InputError::new(3, 1, "NNN", "Expected 2 elements"))
+        );
+        assert_eq!(
+            error("NN\n\nNN -> CB"),
+            Some(InputError::new(3, 7, "CB", "Expected 1 element"))
+        );
     }
 
-    fn test(test_file: &str, iterations: u32, expected: u64) -> Result<()> {
-        let input = crate::files::read_string(test_file)?;
+    fn test(name: &str, iterations: u32, expected: u64) -> Result<()> {
+        let input = crate::input::read(name)?;
         let mut polymer = super::Polymer::new(&input)?;
         polymer.pair_insertion_multiple(iterations);
         assert_eq!(polymer.quantity_difference()?, expected);
@@ -1,69 +1,45 @@
-use std::collections::{BinaryHeap, HashMap};
+use crate::{
+    matrix::{Matrix, Point},
+    solution::Solution,
+};
 
-use crate::matrix::{Matrix, Point};
+use anyhow::{anyhow, Result};
 
 impl Matrix {
-    #[allow(dead_code)]
-    fn dijkstra_cost(&self, start: &Point, end: &Point) -> u32 {
-        let mut heap = BinaryHeap::new();
-        let start = State::new(*start, 0);
-        let mut visited = HashMap::new();
-        heap.push(start);
-
-        while let Some(state) = heap.pop() {
-            if state.point == *end {
-                return state.cost;
-            }
-            if visited.contains_key(&state.point) {
-                continue;
-            }
-            visited.insert(state.point, state.cost);
-            for neighbour in self.neighbours(state.point) {
-                if let Some(value) = self.value(&neighbour) {
-                    let cost_to_neighbour = state.cost + *value;
-                    // Already a better route to this point
-                    if let Some(previous_cost) = visited.get(&neighbour) {
-                        if *previous_cost < cost_to_neighbour {
-                            continue;
-                        }
-                    }
-                    heap.push(State {
-                        point: neighbour,
-                        cost: cost_to_neighbour,
-                    })
-                }
-            }
-        }
-        unreachable!("Must find the end")
+    fn lowest_total_risk(&self) -> Result<u32> {
+        let start = Point { x: 0, y: 0 };
+        let end = Point {
+            x: self.max_x - 1,
+            y: self.max_y - 1,
+        };
+        let route = self.dijkstra_route(start, end).ok_or(anyhow!(
+            "No path from {:?} to {:?}",
+            start,
+            end
+        ))?;
+        Ok(route.cost)
     }
 }
 
-// Copied from the example on https://doc.rust-lang.org/std/collections/binary_heap/index.html
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct State {
-    point: Point,
-    cost: u32,
-}
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u32 = 15;
 
-impl State {
-    fn new(point: Point, cost: u32) -> State {
-        State { point, cost }
+    type Input = Matrix;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Matrix::new(input)
     }
-}
 
-// The priority queue depends on `Ord`.
-// Explicitly implement the trait so the queue becomes a min-heap
-// instead of a max-heap.
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        // Notice that the we flip the ordering on costs.
-        other.cost.cmp(&self.cost)
+    fn part_1(matrix: Self::Input) -> Result<Self::Answer1> {
+        matrix.lowest_total_risk()
     }
-}
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+    fn part_2(matrix: Self::Input) -> Result<Self::Answer2> {
+        matrix.construct_extended_matrix().lowest_total_risk()
     }
 }
 
@@ -74,26 +50,26 @@ mod tests {
 
     #[test]
     fn part_1_test() -> Result<()> {
-        test("inputs/day15-test.txt", false, 40)
+        test("day15-test", false, 40)
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        test("inputs/day15.txt", false, 696)
+        test("day15", false, 696)
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        test("inputs/day15-test.txt", true, 315)
+        test("day15-test", true, 315)
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        test("inputs/day15.txt", true, 2952)
+        test("day15", true, 2952)
     }
 
-    fn test(test_file: &str, construct_extended: bool, expected: u32) -> Result<()> {
-        let input = crate::files::read_string(test_file)?;
+    fn test(name: &str, construct_extended: bool, expected: u32) -> Result<()> {
+        let input = crate::input::read(name)?;
         let matrix = if construct_extended {
             super::Matrix::new(&input)?.construct_extended_matrix()
         } else {
@@ -104,7 +80,39 @@ mod tests {
             x: matrix.max_x - 1,
             y: matrix.max_y - 1,
         };
-        assert_eq!(matrix.dijkstra_cost(&start, &end), expected);
+        let route = matrix.dijkstra_route(start, end).unwrap();
+        assert_eq!(route.path.first(), Some(&start));
+        assert_eq!(route.path.last(), Some(&end));
+        assert_eq!(route.cost, expected);
+        assert_eq!(matrix.lowest_total_risk()?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_tiling() -> Result<()> {
+        let input = crate::input::read("day15-test")?;
+        let matrix = super::Matrix::new(&input)?;
+        let tiling = matrix.tiled(5, 5, super::Matrix::extended_value);
+        let end = Point {
+            x: tiling.max_x - 1,
+            y: tiling.max_y - 1,
+        };
+        let neighbours = |point: &Point| {
+            tiling
+                .neighbours(*point)
+                .map(|n| (n, tiling.value(&n).unwrap()))
+                .collect::<Vec<(Point, u32)>>()
+        };
+        let route = crate::search::dijkstra(Point { x: 0, y: 0 }, neighbours, |p| *p == end);
+        assert_eq!(route.map(|(_, cost)| cost), Some(315));
+        Ok(())
+    }
+
+    #[test]
+    fn render_path() -> Result<()> {
+        let matrix = super::Matrix::new("19\n11")?;
+        let route = matrix.dijkstra_route(Point { x: 0, y: 0 }, Point { x: 1, y: 1 });
+        assert_eq!(matrix.render_path(&route.unwrap().path), "1*9 \n1*1*\n");
         Ok(())
     }
 }
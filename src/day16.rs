@@ -2,12 +2,12 @@ use std::collections::VecDeque;
 
 use crate::{
     hexreader::HexReader,
-    packet::{Packet, PacketContent, ReadFrom},
+    packet::{Packet, PacketContent},
+    solution::Solution,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-#[allow(dead_code)]
 fn packet_version_sum_all(input: &[String]) -> Result<u16> {
     Ok(input
         .iter()
@@ -18,8 +18,8 @@ fn packet_version_sum_all(input: &[String]) -> Result<u16> {
 }
 
 fn packet_version_sum(input: &str) -> Result<u16> {
-    let mut reader = HexReader::new(input);
-    let packet = Packet::read_from(&mut reader)?;
+    let mut reader = HexReader::new(input)?;
+    let packet = Packet::decode(&mut reader)?;
     let mut version_sum = 0;
     let mut q = VecDeque::new();
     q.push_back(packet);
@@ -28,19 +28,40 @@ fn packet_version_sum(input: &str) -> Result<u16> {
         match packet.content {
             PacketContent::Literal(_) => {}
             PacketContent::NSubPackets(sub_packets) => {
-                q.extend(sub_packets.into_iter());
+                q.extend(sub_packets);
             }
-            PacketContent::SubPacketsInBits(sub_packets) => q.extend(sub_packets.into_iter()),
+            PacketContent::SubPacketsInBits(sub_packets) => q.extend(sub_packets),
         }
     }
     Ok(version_sum)
 }
 
-#[allow(dead_code)]
 fn packet_evalate(input: &str) -> Result<u64> {
-    let mut reader = HexReader::new(input);
-    let packet = Packet::read_from(&mut reader)?;
-    Ok(packet.evaluate())
+    let mut reader = HexReader::new(input)?;
+    let packet = Packet::decode(&mut reader)?;
+    Ok(packet.evaluate()?)
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+
+    type Input = Vec<String>;
+    type Answer1 = u16;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(crate::files::parse_lines(input))
+    }
+
+    fn part_1(input: Self::Input) -> Result<Self::Answer1> {
+        packet_version_sum_all(&input)
+    }
+
+    fn part_2(input: Self::Input) -> Result<Self::Answer2> {
+        packet_evalate(input.first().ok_or(anyhow!("Empty input"))?)
+    }
 }
 
 #[cfg(test)]
@@ -49,21 +70,21 @@ mod tests {
 
     #[test]
     fn part_1_test() -> Result<()> {
-        let input = crate::files::read_lines("inputs/day16-test.txt")?;
+        let input = crate::files::parse_lines(&crate::input::read("day16-test")?);
         assert_eq!(super::packet_version_sum_all(&input)?, 82);
         Ok(())
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        let input = crate::files::read_lines("inputs/day16.txt")?;
+        let input = crate::files::parse_lines(&crate::input::read("day16")?);
         assert_eq!(super::packet_version_sum_all(&input)?, 974);
         Ok(())
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        let input = crate::files::read_string("inputs/day16.txt")?;
+        let input = crate::input::read("day16")?;
         assert_eq!(super::packet_evalate(&input)?, 180616437720);
         Ok(())
     }
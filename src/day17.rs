@@ -1,6 +1,8 @@
 use anyhow::{anyhow, Result};
 use regex::Regex;
 
+use crate::solution::Solution;
+
 #[derive(Debug)]
 struct Projectile {
     x: i32,
@@ -48,10 +50,13 @@ impl Projectile {
     }
 }
 
-#[allow(dead_code)]
-fn find_best_point(input: &str) -> Result<(i32, usize)> {
-    // target area: x=20..30, y=-10..-5
-    let (end_x, end_y) = parse_input(input)?;
+pub struct TargetArea {
+    end_x: (i32, i32),
+    end_y: (i32, i32),
+}
+
+fn find_best_point(target: &TargetArea) -> Result<(i32, usize)> {
+    let (end_x, end_y) = (target.end_x, target.end_y);
     let mut results = Vec::new();
     for vel_x in 0..95 {
         for vel_y in -200..200 {
@@ -83,7 +88,8 @@ fn find_best_point(input: &str) -> Result<(i32, usize)> {
     Ok((highest_point, results.len()))
 }
 
-fn parse_input(input: &str) -> Result<((i32, i32), (i32, i32))> {
+// target area: x=20..30, y=-10..-5
+fn parse_input(input: &str) -> Result<TargetArea> {
     let re: Regex = Regex::new("target area: x=(?P<end_x_0>[-0-9]*)\\.\\.(?P<end_x_1>[-0-9]*), y=(?P<end_y_0>[-0-9]*)\\.\\.(?P<end_y_1>[-0-9]*)").unwrap();
     let caps = re.captures(input).ok_or(anyhow!("Input parsing failed"))?;
     let end_x = (
@@ -95,7 +101,29 @@ fn parse_input(input: &str) -> Result<((i32, i32), (i32, i32))> {
         caps["end_y_1"].parse::<i32>()?,
     );
 
-    Ok((end_x, end_y))
+    Ok(TargetArea { end_x, end_y })
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u32 = 17;
+
+    type Input = TargetArea;
+    type Answer1 = i32;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(target: Self::Input) -> Result<Self::Answer1> {
+        Ok(find_best_point(&target)?.0)
+    }
+
+    fn part_2(target: Self::Input) -> Result<Self::Answer2> {
+        Ok(find_best_point(&target)?.1)
+    }
 }
 
 #[cfg(test)]
@@ -104,42 +132,42 @@ mod tests {
 
     #[test]
     fn parse_input() -> Result<()> {
-        let input = crate::files::read_string("inputs/day17.txt")?;
-        let (end_x, end_y) = super::parse_input(&input)?;
+        let input = crate::input::read("day17")?;
+        let target = super::parse_input(&input)?;
 
-        assert_eq!(end_x, (60, 94));
-        assert_eq!(end_y, (-171, -136));
+        assert_eq!(target.end_x, (60, 94));
+        assert_eq!(target.end_y, (-171, -136));
         Ok(())
     }
 
     #[test]
     fn part_1_test() -> Result<()> {
-        let input = crate::files::read_string("inputs/day17-test.txt")?;
-        assert_eq!(super::find_best_point(&input)?.0, 45);
+        let target = super::parse_input(&crate::input::read("day17-test")?)?;
+        assert_eq!(super::find_best_point(&target)?.0, 45);
 
         Ok(())
     }
 
     #[test]
     fn part_1_real() -> Result<()> {
-        let input = crate::files::read_string("inputs/day17.txt")?;
-        assert_eq!(super::find_best_point(&input)?.0, 14535);
+        let target = super::parse_input(&crate::input::read("day17")?)?;
+        assert_eq!(super::find_best_point(&target)?.0, 14535);
 
         Ok(())
     }
 
     #[test]
     fn part_2_test() -> Result<()> {
-        let input = crate::files::read_string("inputs/day17-test.txt")?;
-        assert_eq!(super::find_best_point(&input)?.1, 112);
+        let target = super::parse_input(&crate::input::read("day17-test")?)?;
+        assert_eq!(super::find_best_point(&target)?.1, 112);
 
         Ok(())
     }
 
     #[test]
     fn part_2_real() -> Result<()> {
-        let input = crate::files::read_string("inputs/day17.txt")?;
-        assert_eq!(super::find_best_point(&input)?.1, 2270);
+        let target = super::parse_input(&crate::input::read("day17")?)?;
+        assert_eq!(super::find_best_point(&target)?.1, 2270);
 
         Ok(())
     }
@@ -2,31 +2,33 @@
     This is copied verbatim from https://github.com/armsnyder/aoc2021/.
     I figured it's better to copy and move on to the next one than abandon this year's edition.
     There's plenty I can learn from this code as well. Like how he implements From<&str>
-    instead of creating a new() method.
+    instead of creating a new() method. That's since become FromStr, so bad
+    input is an error instead of a panic.
 */
-use std::io::BufRead;
 use std::iter::Sum;
 use std::ops::Add;
+use std::str::FromStr;
 
-#[allow(dead_code)]
-fn part1<R: BufRead>(reader: R) -> String {
-    reader
+use anyhow::{anyhow, Result};
+
+use crate::solution::Solution;
+
+fn parse_numbers(input: &str) -> Result<Vec<Number>> {
+    let numbers = input
         .lines()
-        .map(Result::unwrap)
-        .map(Number::from)
-        .sum::<Number>()
-        .magnitude()
-        .to_string()
+        .map(Number::from_str)
+        .collect::<Result<Vec<Number>>>()?;
+    if numbers.is_empty() {
+        return Err(anyhow!("No numbers"));
+    }
+    Ok(numbers)
 }
 
-#[allow(dead_code)]
-fn part2<R: BufRead>(reader: R) -> String {
-    let numbers = reader
-        .lines()
-        .map(Result::unwrap)
-        .map(Number::from)
-        .collect::<Vec<Number>>();
+fn part1(numbers: &[Number]) -> i32 {
+    numbers.iter().cloned().sum::<Number>().magnitude()
+}
 
+fn part2(numbers: &[Number]) -> i32 {
     let mut max_magnitude = 0;
 
     for i in 0..numbers.len() {
@@ -40,11 +42,11 @@ fn part2<R: BufRead>(reader: R) -> String {
         }
     }
 
-    max_magnitude.to_string()
+    max_magnitude
 }
 
 #[derive(Debug, PartialEq, Clone)]
-enum Number {
+pub enum Number {
     Single(i32),
     Pair(Box<Number>, Box<Number>),
 }
@@ -158,37 +160,58 @@ impl Sum for Number {
     }
 }
 
-impl From<&str> for Number {
-    fn from(s: &str) -> Self {
-        if s.starts_with('[') {
-            let mut depth = 0;
-            let mut comma_index = 0;
-            for (i, c) in s.chars().enumerate() {
+impl FromStr for Number {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some(inner) = s.strip_prefix('[') else {
+            let value = s
+                .parse()
+                .map_err(|e| anyhow!("Bad number {:?}: {}", s, e))?;
+            return Ok(Number::Single(value));
+        };
+        let inner = inner
+            .strip_suffix(']')
+            .ok_or_else(|| anyhow!("Unclosed pair {:?}", s))?;
+        let mut depth = 0;
+        let comma_index = inner
+            .char_indices()
+            .find(|(_, c)| {
                 match c {
                     '[' => depth += 1,
                     ']' => depth -= 1,
-                    ',' => {
-                        if depth == 1 {
-                            comma_index = i;
-                            break;
-                        }
-                    }
                     _ => (),
                 }
-            }
-            Number::Pair(
-                Box::new(Number::from(&s[1..comma_index])),
-                Box::new(Number::from(&s[comma_index + 1..s.len() - 1])),
-            )
-        } else {
-            Number::Single(s.parse().unwrap())
-        }
+                *c == ',' && depth == 0
+            })
+            .map(|(i, _)| i)
+            .ok_or_else(|| anyhow!("Pair without a comma {:?}", s))?;
+        Ok(Number::Pair(
+            Box::new(inner[..comma_index].parse()?),
+            Box::new(inner[comma_index + 1..].parse()?),
+        ))
     }
 }
 
-impl From<String> for Number {
-    fn from(s: String) -> Self {
-        Number::from(s.as_str())
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+
+    type Input = Vec<Number>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_numbers(input)
+    }
+
+    fn part_1(numbers: Self::Input) -> Result<Self::Answer1> {
+        Ok(part1(&numbers))
+    }
+
+    fn part_2(numbers: Self::Input) -> Result<Self::Answer2> {
+        Ok(part2(&numbers))
     }
 }
 
@@ -196,17 +219,26 @@ impl From<String> for Number {
 mod tests {
     #[test]
     fn part_1_real() -> anyhow::Result<()> {
-        let reader = crate::files::buf_reader("inputs/day18.txt")?;
-        assert_eq!(super::part1(reader), "4132");
+        let numbers = super::parse_numbers(&crate::input::read("day18")?)?;
+        assert_eq!(super::part1(&numbers), 4132);
 
         Ok(())
     }
 
     #[test]
     fn part_2_real() -> anyhow::Result<()> {
-        let reader = crate::files::buf_reader("inputs/day18.txt")?;
-        assert_eq!(super::part2(reader), "4685");
+        let numbers = super::parse_numbers(&crate::input::read("day18")?)?;
+        assert_eq!(super::part2(&numbers), 4685);
 
         Ok(())
     }
+
+    #[test]
+    fn parse_errors() {
+        assert!(super::parse_numbers("[[1,2],3]").is_ok());
+        assert!(super::parse_numbers("[1,2").is_err());
+        assert!(super::parse_numbers("[12]").is_err());
+        assert!(super::parse_numbers("[1,x]").is_err());
+        assert!(super::parse_numbers("").is_err());
+    }
 }
@@ -1,263 +1,28 @@
-use std::ops::{Add, Index, Mul, MulAssign, Neg, Sub};
-
-pub type Coord = i32;
-
-#[derive(Default, Eq, PartialEq, Hash, Copy, Clone, Ord, PartialOrd)]
-pub struct Vector3 {
-    pub x: Coord,
-    pub y: Coord,
-    pub z: Coord,
-}
-
-impl Vector3 {
-    pub fn manhattan_distance(self) -> Coord {
-        self.x.abs() + self.y.abs() + self.z.abs()
-    }
-}
-
-impl From<String> for Vector3 {
-    fn from(s: String) -> Self {
-        let mut output = Vector3::default();
-        let mut split = s.split(',');
-        output.x = split.next().unwrap().parse().unwrap();
-        output.y = split.next().unwrap().parse().unwrap();
-        output.z = split.next().unwrap().parse().unwrap();
-        output
-    }
-}
-
-impl Sub for Vector3 {
-    type Output = Self;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Vector3 {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-            z: self.z - rhs.z,
-        }
-    }
-}
-
-impl Neg for Vector3 {
-    type Output = Self;
-
-    fn neg(self) -> Self::Output {
-        Vector3 {
-            x: -self.x,
-            y: -self.y,
-            z: -self.z,
-        }
-    }
-}
-
-#[derive(Copy, Clone)]
-pub struct Matrix4([[Coord; 4]; 4]);
-
-impl Mul for Matrix4 {
-    type Output = Self;
-
-    fn mul(self, rhs: Self) -> Self::Output {
-        Matrix4([
-            [
-                self[0][0] * rhs[0][0]
-                    + self[0][1] * rhs[1][0]
-                    + self[0][2] * rhs[2][0]
-                    + self[0][3] * rhs[3][0],
-                self[0][0] * rhs[0][1]
-                    + self[0][1] * rhs[1][1]
-                    + self[0][2] * rhs[2][1]
-                    + self[0][3] * rhs[3][1],
-                self[0][0] * rhs[0][2]
-                    + self[0][1] * rhs[1][2]
-                    + self[0][2] * rhs[2][2]
-                    + self[0][3] * rhs[3][2],
-                self[0][0] * rhs[0][3]
-                    + self[0][1] * rhs[1][3]
-                    + self[0][2] * rhs[2][3]
-                    + self[0][3] * rhs[3][3],
-            ],
-            [
-                self[1][0] * rhs[0][0]
-                    + self[1][1] * rhs[1][0]
-                    + self[1][2] * rhs[2][0]
-                    + self[1][3] * rhs[3][0],
-                self[1][0] * rhs[0][1]
-                    + self[1][1] * rhs[1][1]
-                    + self[1][2] * rhs[2][1]
-                    + self[1][3] * rhs[3][1],
-                self[1][0] * rhs[0][2]
-                    + self[1][1] * rhs[1][2]
-                    + self[1][2] * rhs[2][2]
-                    + self[1][3] * rhs[3][2],
-                self[1][0] * rhs[0][3]
-                    + self[1][1] * rhs[1][3]
-                    + self[1][2] * rhs[2][3]
-                    + self[1][3] * rhs[3][3],
-            ],
-            [
-                self[2][0] * rhs[0][0]
-                    + self[2][1] * rhs[1][0]
-                    + self[2][2] * rhs[2][0]
-                    + self[2][3] * rhs[3][0],
-                self[2][0] * rhs[0][1]
-                    + self[2][1] * rhs[1][1]
-                    + self[2][2] * rhs[2][1]
-                    + self[2][3] * rhs[3][1],
-                self[2][0] * rhs[0][2]
-                    + self[2][1] * rhs[1][2]
-                    + self[2][2] * rhs[2][2]
-                    + self[2][3] * rhs[3][2],
-                self[2][0] * rhs[0][3]
-                    + self[2][1] * rhs[1][3]
-                    + self[2][2] * rhs[2][3]
-                    + self[2][3] * rhs[3][3],
-            ],
-            [
-                self[3][0] * rhs[0][0]
-                    + self[3][1] * rhs[1][0]
-                    + self[3][2] * rhs[2][0]
-                    + self[3][3] * rhs[3][0],
-                self[3][0] * rhs[0][1]
-                    + self[3][1] * rhs[1][1]
-                    + self[3][2] * rhs[2][1]
-                    + self[3][3] * rhs[3][1],
-                self[3][0] * rhs[0][2]
-                    + self[3][1] * rhs[1][2]
-                    + self[3][2] * rhs[2][2]
-                    + self[3][3] * rhs[3][2],
-                self[3][0] * rhs[0][3]
-                    + self[3][1] * rhs[1][3]
-                    + self[3][2] * rhs[2][3]
-                    + self[3][3] * rhs[3][3],
-            ],
-        ])
-    }
-}
-
-impl MulAssign for Matrix4 {
-    fn mul_assign(&mut self, rhs: Self) {
-        *self = *self * rhs;
-    }
-}
-
-impl Mul<Vector3> for Matrix4 {
-    type Output = Vector3;
-
-    fn mul(self, rhs: Vector3) -> Self::Output {
-        Vector3 {
-            x: self[0][0] * rhs.x + self[0][1] * rhs.y + self[0][2] * rhs.z + self[0][3],
-            y: self[1][0] * rhs.x + self[1][1] * rhs.y + self[1][2] * rhs.z + self[1][3],
-            z: self[2][0] * rhs.x + self[2][1] * rhs.y + self[2][2] * rhs.z + self[2][3],
-        }
-    }
-}
-
-impl Add<Vector3> for Matrix4 {
-    type Output = Self;
-
-    fn add(self, rhs: Vector3) -> Self::Output {
-        Matrix4([
-            [self[0][0], self[0][1], self[0][2], self[0][3] + rhs.x],
-            [self[1][0], self[1][1], self[1][2], self[1][3] + rhs.y],
-            [self[2][0], self[2][1], self[2][2], self[2][3] + rhs.z],
-            [self[3][0], self[3][1], self[3][2], self[3][3]],
-        ])
-    }
-}
-
-impl Index<usize> for Matrix4 {
-    type Output = [Coord; 4];
-
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.0[index]
-    }
-}
-
-impl Matrix4 {
-    pub const IDENTITY: Matrix4 = Matrix4([[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]]);
-
-    pub const ROTATE_X_90: Matrix4 =
-        Matrix4([[1, 0, 0, 0], [0, 0, -1, 0], [0, 1, 0, 0], [0, 0, 0, 1]]);
+use std::collections::{HashMap, HashSet};
+use std::ops::Mul;
 
-    pub const ROTATE_Y_90: Matrix4 =
-        Matrix4([[0, 0, 1, 0], [0, 1, 0, 0], [-1, 0, 0, 0], [0, 0, 0, 1]]);
+use anyhow::{anyhow, Result};
 
-    pub const ROTATE_Z_90: Matrix4 =
-        Matrix4([[0, -1, 0, 0], [1, 0, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]]);
+use crate::{
+    geometry::{Coord, Matrix4, Vector3, ALL_ORIENTATIONS},
+    solution::Solution,
+};
 
-    pub fn translation(self) -> Vector3 {
-        Vector3 {
-            x: self[0][3],
-            y: self[1][3],
-            z: self[2][3],
-        }
-    }
-}
-use lazy_static::lazy_static;
-
-lazy_static! {
-    pub static ref ALL_ORIENTATIONS: Vec<Matrix4> = {
-        let rotate_x_180 = Matrix4::ROTATE_X_90 * Matrix4::ROTATE_X_90;
-        let rotate_x_270 = rotate_x_180 * Matrix4::ROTATE_X_90;
-        let rotate_y_180 = Matrix4::ROTATE_Y_90 * Matrix4::ROTATE_Y_90;
-        let rotate_y_270 = rotate_y_180 * Matrix4::ROTATE_Y_90;
-        let rotate_z_180 = Matrix4::ROTATE_Z_90 * Matrix4::ROTATE_Z_90;
-        let rotate_z_270 = rotate_z_180 * Matrix4::ROTATE_Z_90;
-
-        // All 24 orientations, using +x as default face.
-        vec![
-            // +x
-            Matrix4::IDENTITY,
-            Matrix4::ROTATE_X_90,
-            rotate_x_180,
-            rotate_x_270,
-            // -x
-            rotate_z_180,
-            rotate_z_180 * Matrix4::ROTATE_X_90,
-            rotate_z_180 * rotate_x_180,
-            rotate_z_180 * rotate_x_270,
-            // +y
-            rotate_z_270,
-            rotate_z_270 * Matrix4::ROTATE_Y_90,
-            rotate_z_270 * rotate_y_180,
-            rotate_z_270 * rotate_y_270,
-            // -y
-            Matrix4::ROTATE_Z_90,
-            Matrix4::ROTATE_Z_90 * Matrix4::ROTATE_Y_90,
-            Matrix4::ROTATE_Z_90 * rotate_y_180,
-            Matrix4::ROTATE_Z_90 * rotate_y_270,
-            // +z
-            Matrix4::ROTATE_Y_90,
-            Matrix4::ROTATE_Y_90 * Matrix4::ROTATE_Z_90,
-            Matrix4::ROTATE_Y_90 * rotate_z_180,
-            Matrix4::ROTATE_Y_90 * rotate_z_270,
-            // -z
-            rotate_y_270,
-            rotate_y_270 * Matrix4::ROTATE_Z_90,
-            rotate_y_270 * rotate_z_180,
-            rotate_y_270 * rotate_z_270,
-        ]
-    };
+fn part1(regions: Vec<Region>) -> Result<usize> {
+    let region_transforms = get_region_transforms(&regions)?;
+    Ok(count_beacons(regions, region_transforms))
 }
 
-use std::collections::{HashMap, HashSet};
-use std::io::{BufRead, Lines};
-
-#[allow(dead_code)]
-fn part1<R: BufRead>(reader: R) -> String {
-    let regions = read_regions(reader);
-    let region_transforms = get_region_transforms(&regions);
-    count_beacons(regions, region_transforms).to_string()
+fn part2(regions: &[Region]) -> Result<Coord> {
+    let region_transforms = get_region_transforms(regions)?;
+    Ok(get_largest_manhattan_distance_between_scanners(
+        region_transforms,
+    ))
 }
 
-#[allow(dead_code)]
-fn part2<R: BufRead>(reader: R) -> String {
-    let regions = read_regions(reader);
-    let region_transforms = get_region_transforms(&regions);
-    get_largest_manhattan_distance_between_scanners(region_transforms).to_string()
-}
-
-fn get_region_transforms(regions: &[Region]) -> Vec<Matrix4> {
+// Every scanner has to be linked to scanner 0 through a chain of overlaps,
+// otherwise there's no way to place it.
+fn get_region_transforms(regions: &[Region]) -> Result<Vec<Matrix4>> {
     let mut region_transforms = vec![Matrix4::IDENTITY; regions.len()];
     let mut visited = vec![false; regions.len()];
     let mut queue = vec![(0, Matrix4::IDENTITY)];
@@ -276,7 +41,10 @@ fn get_region_transforms(regions: &[Region]) -> Vec<Matrix4> {
         }
     }
 
-    region_transforms
+    match visited.iter().position(|visited| !visited) {
+        Some(i) => Err(anyhow!("Scanner {} doesn't overlap the others", i)),
+        None => Ok(region_transforms),
+    }
 }
 
 fn count_beacons(regions: Vec<Region>, region_transforms: Vec<Matrix4>) -> usize {
@@ -289,6 +57,7 @@ fn count_beacons(regions: Vec<Region>, region_transforms: Vec<Matrix4>) -> usize
         .len()
 }
 
+// 0 for a lone scanner.
 fn get_largest_manhattan_distance_between_scanners(region_transforms: Vec<Matrix4>) -> Coord {
     let translations = region_transforms
         .into_iter()
@@ -305,30 +74,35 @@ fn get_largest_manhattan_distance_between_scanners(region_transforms: Vec<Matrix
                 .map(Vector3::manhattan_distance)
         })
         .max()
-        .unwrap()
+        .unwrap_or(0)
 }
 
-fn read_regions<R: BufRead>(reader: R) -> Vec<Region> {
+fn read_regions(input: &str) -> Result<Vec<Region>> {
     let mut regions = Vec::new();
-    let lines = &mut reader.lines();
+    let lines = &mut input.lines();
     while let Some(region) = next_region(lines) {
-        regions.push(region);
+        regions.push(region?);
     }
-    regions
+    if regions.is_empty() {
+        return Err(anyhow!("No scanners"));
+    }
+    Ok(regions)
 }
 
-fn next_region<R: BufRead>(lines: &mut Lines<R>) -> Option<Region> {
-    lines.next().map(|_| {
+fn next_region<'a>(lines: &mut impl Iterator<Item = &'a str>) -> Option<Result<Region>> {
+    lines.next().map(|header| {
+        if !header.starts_with("--- scanner ") {
+            return Err(anyhow!("Expected a scanner header, found {:?}", header));
+        }
         lines
-            .map(Result::unwrap)
             .take_while(|line| !line.is_empty())
-            .map(Vector3::from)
-            .collect::<Region>()
+            .map(str::parse)
+            .collect::<Result<Region>>()
     })
 }
 
 #[derive(Default)]
-struct Region {
+pub struct Region {
     // The beacon positions:
     beacons: Vec<Vector3>,
     // Markers for finding overlapping regions (distances between every combination of 2 beacons):
@@ -373,14 +147,14 @@ impl Region {
                     let translation = overlapping_orientation_markers
                         .into_iter()
                         .next()
-                        .map(|(k, v)| other.orientation_markers[&k] - v)
-                        .unwrap();
+                        .map(|(k, v)| other.orientation_markers[&k] - v)?;
 
                     return Some(try_rotation + translation);
                 }
             }
 
-            unreachable!()
+            // Enough matching distances, but no rotation lines them up.
+            None
         }
     }
 }
@@ -408,22 +182,66 @@ impl FromIterator<Vector3> for Region {
         region
     }
 }
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u32 = 19;
+
+    type Input = Vec<Region>;
+    type Answer1 = usize;
+    type Answer2 = Coord;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        read_regions(input)
+    }
+
+    fn part_1(regions: Self::Input) -> anyhow::Result<Self::Answer1> {
+        part1(regions)
+    }
+
+    fn part_2(regions: Self::Input) -> anyhow::Result<Self::Answer2> {
+        part2(&regions)
+    }
+}
+
+#[cfg(test)]
 mod tests {
     #[test]
     fn test_part1() -> anyhow::Result<()> {
-        assert_eq!(
-            super::part1(crate::files::buf_reader("inputs/day19.txt")?),
-            "330"
-        );
+        let regions = super::read_regions(&crate::input::read("day19")?)?;
+        assert_eq!(super::part1(regions)?, 330);
         Ok(())
     }
 
     #[test]
     fn test_part2() -> anyhow::Result<()> {
+        let regions = super::read_regions(&crate::input::read("day19")?)?;
+        assert_eq!(super::part2(&regions)?, 9634);
+        Ok(())
+    }
+
+    #[test]
+    fn lone_and_disconnected_scanners() -> anyhow::Result<()> {
+        let lone = super::read_regions("--- scanner 0 ---\n1,2,3\n")?;
+        assert_eq!(super::part2(&lone)?, 0);
+
+        let apart = "--- scanner 0 ---\n1,2,3\n\n--- scanner 1 ---\n4,5,6\n";
+        let error = super::part1(super::read_regions(apart)?).unwrap_err();
+        assert_eq!(error.to_string(), "Scanner 1 doesn't overlap the others");
+        Ok(())
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |input| super::read_regions(input).err().unwrap().to_string();
+        assert_eq!(error(""), "No scanners");
         assert_eq!(
-            super::part2(crate::files::buf_reader("inputs/day19.txt")?),
-            "9634"
+            error("1,2,3\n"),
+            "Expected a scanner header, found \"1,2,3\""
+        );
+        assert_eq!(
+            error("--- scanner 0 ---\n1,2\n"),
+            "Expected 3 coordinates, found \"1,2\""
         );
-        Ok(())
     }
 }
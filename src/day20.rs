@@ -1,74 +1,54 @@
-use std::{collections::HashSet, fmt::Write};
+use anyhow::{anyhow, Result};
 
-#[allow(dead_code)]
-fn csi_enhance(input: &str, times: u32) -> usize {
-    let algorithm = Algorithm::from(input);
-    let mut image = Image::from(input);
+use crate::{
+    solution::Solution,
+    sparse::{Point, SparseGrid},
+};
 
+fn csi_enhance(algorithm: &Algorithm, mut image: Image, times: u32) -> usize {
     for _ in 0..times {
-        image = image.enhance(&algorithm);
+        image = image.enhance(algorithm);
     }
 
-    image.light_pixels.len()
+    image.pixels.values().filter(|light| **light).count()
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-struct Point {
-    x: i32,
-    y: i32,
+// Pixels outside the bounding box all share the grid's default, the background.
+pub struct Image {
+    pixels: SparseGrid<bool>,
 }
 
-struct Image {
-    light_pixels: HashSet<Point>,
-    min_x: i32,
-    max_x: i32,
-    min_y: i32,
-    max_y: i32,
-    light_background: bool,
+pub struct Algorithm(Vec<bool>);
+
+fn parse_input(input: &str) -> Result<(Algorithm, Image)> {
+    let mut lines = input.lines();
+    let algorithm = Algorithm::parse(lines.next().unwrap_or_default())?;
+    if lines.next().is_some_and(|line| !line.is_empty()) {
+        return Err(anyhow!("Expected a blank line after the algorithm"));
+    }
+    let image = Image::parse(lines)?;
+    Ok((algorithm, image))
 }
 
-struct Algorithm(Vec<char>);
+fn parse_pixel(c: char) -> Result<bool> {
+    match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err(anyhow!("Unexpected character {:?}", c)),
+    }
+}
 
 impl Image {
     fn enhance(&self, algorithm: &Algorithm) -> Self {
-        let mut new_light_pixels = HashSet::new();
-        for x in self.min_x - 1..self.max_x + 1 {
-            for y in self.min_y - 1..self.max_y + 1 {
-                let point = Point { x, y };
+        let light_background = !self.pixels.default_value() && algorithm.is_light_pixel(0);
+        let mut pixels = SparseGrid::new(light_background);
+        if let Some(bounds) = self.pixels.bounds() {
+            for point in bounds.grow(1).points() {
                 let index = self.enhanced_index(&point);
-                if algorithm.is_light_pixel(index) {
-                    new_light_pixels.insert(point);
-                }
-            }
-        }
-        Image {
-            light_pixels: new_light_pixels,
-            min_x: self.min_x - 1,
-            max_x: self.max_x + 1,
-            min_y: self.min_y - 1,
-            max_y: self.max_y + 1,
-            light_background: !self.light_background && algorithm.is_light_pixel(0),
-        }
-    }
-
-    fn is_out_of_bounds(&self, point: &Point) -> bool {
-        point.x < self.min_x
-            || point.x >= self.max_x
-            || point.y < self.min_y
-            || point.y >= self.max_y
-    }
-
-    fn get(&self, point: &Point) -> char {
-        match self.light_pixels.get(point) {
-            Some(_) => '#',
-            None => {
-                if self.is_out_of_bounds(point) && self.light_background {
-                    '#'
-                } else {
-                    '.'
-                }
+                pixels.set(point, algorithm.is_light_pixel(index));
             }
         }
+        Image { pixels }
     }
 
     fn enhanced_index(&self, point: &Point) -> usize {
@@ -76,10 +56,8 @@ impl Image {
         for y in (point.y - 1)..=(point.y + 1) {
             for x in (point.x - 1)..=(point.x + 1) {
                 result <<= 1;
-                match self.get(&Point { x, y }) {
-                    '#' => result += 1,
-                    '.' => {}
-                    _ => unreachable!("Invalid character"),
+                if *self.pixels.get(&Point { x, y }) {
+                    result += 1;
                 }
             }
         }
@@ -88,63 +66,74 @@ impl Image {
     }
 }
 
-impl From<&str> for Image {
-    fn from(input: &str) -> Self {
-        let mut light_pixels = HashSet::new();
-        for (y, line) in input.lines().skip(2).enumerate() {
+impl Image {
+    fn parse<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Self> {
+        let mut pixels = SparseGrid::new(false);
+        for (y, line) in lines.enumerate() {
             for (x, val) in line.chars().enumerate() {
-                let point = Point {
-                    x: x as i32,
-                    y: y as i32,
-                };
-                if val == '#' {
-                    light_pixels.insert(point);
-                }
+                let light = parse_pixel(val).map_err(|e| {
+                    anyhow!("Bad image pixel at line {}, column {}: {}", y + 3, x + 1, e)
+                })?;
+                pixels.set(Point::new(x as i32, y as i32), light);
             }
         }
-        let min_x = 0;
-        let max_x = input.lines().nth(2).unwrap().chars().count() as i32;
-        let min_y = 0;
-        let max_y = input.lines().skip(2).count() as i32;
-        let light_background = false;
-        Image {
-            light_pixels,
-            min_x,
-            max_x,
-            min_y,
-            max_y,
-            light_background,
-        }
+        Ok(Image { pixels })
     }
 }
 
 impl std::fmt::Display for Image {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let x = format!(
-            "{} {} {} {}\n",
-            self.min_x, self.max_x, self.min_y, self.max_y
-        );
-        f.write_str(&x)?;
-        for y in self.min_y..self.max_y {
-            for x in self.min_x..self.max_x {
-                f.write_char(self.get(&Point { x, y }))?;
-            }
-            f.write_char('\n')?;
-        }
-        f.write_char('\n')?;
-        Ok(())
+        f.write_str(&self.pixels.render(|light| if *light { '#' } else { '.' }))
     }
 }
 
-impl From<&str> for Algorithm {
-    fn from(input: &str) -> Self {
-        Algorithm(input.lines().next().unwrap().chars().collect::<Vec<char>>())
+impl Algorithm {
+    // One output pixel for each 9 bit neighbourhood.
+    const LENGTH: usize = 512;
+
+    fn parse(line: &str) -> Result<Self> {
+        let pixels = line
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                parse_pixel(c)
+                    .map_err(|e| anyhow!("Bad algorithm pixel at column {}: {}", i + 1, e))
+            })
+            .collect::<Result<Vec<bool>>>()?;
+        if pixels.len() != Self::LENGTH {
+            return Err(anyhow!(
+                "Expected {} algorithm pixels, found {}",
+                Self::LENGTH,
+                pixels.len()
+            ));
+        }
+        Ok(Algorithm(pixels))
     }
-}
 
-impl Algorithm {
     fn is_light_pixel(&self, index: usize) -> bool {
-        self.0[index] == '#'
+        self.0[index]
+    }
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u32 = 20;
+
+    type Input = (Algorithm, Image);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1((algorithm, image): Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(csi_enhance(&algorithm, image, 2))
+    }
+
+    fn part_2((algorithm, image): Self::Input) -> anyhow::Result<Self::Answer2> {
+        Ok(csi_enhance(&algorithm, image, 50))
     }
 }
 
@@ -152,29 +141,44 @@ impl Algorithm {
 mod tests {
     #[test]
     fn part_1_test() -> anyhow::Result<()> {
-        let input = crate::files::read_string("inputs/day20-test.txt")?;
-        assert_eq!(super::csi_enhance(&input, 2), 35);
+        let (algorithm, image) = super::parse_input(&crate::input::read("day20-test")?)?;
+        assert_eq!(super::csi_enhance(&algorithm, image, 2), 35);
         Ok(())
     }
 
     #[test]
     fn part_1_real() -> anyhow::Result<()> {
-        let input = crate::files::read_string("inputs/day20.txt")?;
-        assert_eq!(super::csi_enhance(&input, 2), 5461);
+        let (algorithm, image) = super::parse_input(&crate::input::read("day20")?)?;
+        assert_eq!(super::csi_enhance(&algorithm, image, 2), 5461);
         Ok(())
     }
 
     #[test]
     fn part_2_test() -> anyhow::Result<()> {
-        let input = crate::files::read_string("inputs/day20-test.txt")?;
-        assert_eq!(super::csi_enhance(&input, 50), 3351);
+        let (algorithm, image) = super::parse_input(&crate::input::read("day20-test")?)?;
+        assert_eq!(super::csi_enhance(&algorithm, image, 50), 3351);
         Ok(())
     }
 
     #[test]
     fn part_2_real() -> anyhow::Result<()> {
-        let input = crate::files::read_string("inputs/day20.txt")?;
-        assert_eq!(super::csi_enhance(&input, 50), 18226);
+        let (algorithm, image) = super::parse_input(&crate::input::read("day20")?)?;
+        assert_eq!(super::csi_enhance(&algorithm, image, 50), 18226);
         Ok(())
     }
+
+    #[test]
+    fn parse_errors() {
+        let error = |input: &str| super::parse_input(input).err().unwrap().to_string();
+        let algorithm = "#".repeat(512);
+        assert_eq!(
+            error(&format!("{}\n\n#.\n.x", algorithm)),
+            "Bad image pixel at line 4, column 2: Unexpected character 'x'"
+        );
+        assert_eq!(error("#.#\n\n#."), "Expected 512 algorithm pixels, found 3");
+        assert_eq!(
+            error(&format!("{}\n#.", algorithm)),
+            "Expected a blank line after the algorithm"
+        );
+    }
 }
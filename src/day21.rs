@@ -3,7 +3,10 @@ use std::{
     ops::{AddAssign, Mul},
 };
 
-#[allow(dead_code)]
+use anyhow::{anyhow, Result};
+
+use crate::solution::Solution;
+
 fn practice_game(x: u32, y: u32) -> u32 {
     let mut x_position = x - 1;
     let mut y_position = y - 1;
@@ -117,7 +120,6 @@ impl Mul<u64> for Wins {
     }
 }
 
-#[allow(dead_code)]
 fn real_game(x_pos: u32, y_pos: u32) -> u64 {
     let mut storage = HashMap::new();
     let initial_state = State {
@@ -163,6 +165,43 @@ fn solve(state: State, storage: &mut HashMap<State, Wins>) -> Wins {
     combined_wins
 }
 
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u32 = 21;
+
+    type Input = (u32, u32);
+    type Answer1 = u32;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_starting_positions(input)
+    }
+
+    fn part_1((x, y): Self::Input) -> Result<Self::Answer1> {
+        Ok(practice_game(x, y))
+    }
+
+    fn part_2((x, y): Self::Input) -> Result<Self::Answer2> {
+        Ok(real_game(x, y))
+    }
+}
+
+// Player 1 starting position: 4
+// Player 2 starting position: 8
+fn parse_starting_positions(input: &str) -> Result<(u32, u32)> {
+    let mut positions = input.lines().map(|line| {
+        line.split_ascii_whitespace()
+            .last()
+            .ok_or(anyhow!("Invalid input"))?
+            .parse::<u32>()
+            .map_err(|e| anyhow!(e))
+    });
+    let x = positions.next().ok_or(anyhow!("Missing player 1"))??;
+    let y = positions.next().ok_or(anyhow!("Missing player 2"))??;
+    Ok((x, y))
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
@@ -190,4 +229,11 @@ mod tests {
         assert_eq!(super::real_game(4, 10), 187451244607486);
         Ok(())
     }
+
+    #[test]
+    fn parse_starting_positions() -> Result<()> {
+        let input = crate::input::read("day21")?;
+        assert_eq!(super::parse_starting_positions(&input)?, (4, 10));
+        Ok(())
+    }
 }
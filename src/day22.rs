@@ -1,5 +1,6 @@
 use std::ops::Sub;
 
+#[allow(dead_code)]
 struct Cuboid {
     x_start: i32,
     x_end: i32,
@@ -10,22 +11,25 @@ struct Cuboid {
     state: State,
 }
 
+#[allow(dead_code)]
 enum State {
     On,
     Off,
 }
 
+#[allow(dead_code)]
 struct Range(i32, i32);
 
 impl Sub for Range {
     type Output = Range;
 
-    fn sub(self, rhs: Self) -> Self::Output {
+    fn sub(self, _rhs: Self) -> Self::Output {
         todo!()
     }
 }
 
 impl Cuboid {
+    #[allow(dead_code, clippy::nonminimal_bool)]
     fn intersect(self, other: &Cuboid) -> (Cuboid, Option<Cuboid>, Option<Cuboid>) {
         let no_x_overlap =
             Cuboid::no_overlap((self.x_start, self.x_end), (other.x_start, other.x_end));
@@ -43,6 +47,7 @@ impl Cuboid {
         (self, None, None)
     }
 
+    #[allow(dead_code)]
     fn no_overlap(l1: (i32, i32), l2: (i32, i32)) -> bool {
         if (l2.0 < l1.0 && l1.0 < l2.1) || (l2.0 < l1.1 && l1.1 < l2.1) {
             return false;
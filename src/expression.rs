@@ -0,0 +1,251 @@
+use anyhow::{anyhow, Result};
+
+use crate::packet::{LengthType, Packet, PacketType};
+
+// The language is what `Packet::infix` prints:
+//
+//   comparison := sum (("<" | ">" | "==") sum)?
+//   sum        := product ("+" product)*
+//   product    := atom ("*" atom)*
+//   atom       := integer | "(" comparison ")" | name "(" arguments? ")"
+//   arguments  := comparison ("," comparison)*
+//
+// where name is one of sum, product, min, max, gt, lt or eq.
+
+/// Parses an expression like `(3 + 5) * min(7, 9)` into packets. Every packet
+/// has version 0 and operators count their sub-packets. Bracketed operators
+/// become packets of their own, so `1 + (2 + 3)` is a sum inside a sum while
+/// `1 + 2 + 3` is one sum.
+pub fn parse(expression: &str) -> Result<Packet> {
+    let mut parser = Parser {
+        tokens: tokenise(expression)?,
+        next: 0,
+        end: expression.len(),
+    };
+    let packet = parser.comparison()?;
+    match parser.peek() {
+        None => Ok(packet),
+        Some(_) => Err(parser.unexpected()),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Integer(u64),
+    Name(String),
+    Open,
+    Close,
+    Comma,
+    Plus,
+    Star,
+    Less,
+    Greater,
+    Equal,
+}
+
+// Each token with the byte offset it starts at.
+fn tokenise(expression: &str) -> Result<Vec<(usize, Token)>> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            '+' => Token::Plus,
+            '*' => Token::Star,
+            '<' => Token::Less,
+            '>' => Token::Greater,
+            '=' if chars.next_if(|(_, c)| *c == '=').is_some() => Token::Equal,
+            c if c.is_ascii_digit() || c.is_ascii_alphabetic() => {
+                let mut end = offset + c.len_utf8();
+                while let Some((next, c)) = chars.next_if(|(_, c)| c.is_ascii_alphanumeric()) {
+                    end = next + c.len_utf8();
+                }
+                let word = &expression[offset..end];
+                if c.is_ascii_digit() {
+                    let value = word.parse().map_err(|e| {
+                        anyhow!("Bad integer {:?} at offset {}: {}", word, offset, e)
+                    })?;
+                    Token::Integer(value)
+                } else {
+                    Token::Name(word.to_owned())
+                }
+            }
+            _ => return Err(anyhow!("Unexpected {:?} at offset {}", c, offset)),
+        };
+        tokens.push((offset, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        if self.peek() != Some(&expected) {
+            return Err(self.unexpected());
+        }
+        self.next += 1;
+        Ok(())
+    }
+
+    fn unexpected(&self) -> anyhow::Error {
+        match self.tokens.get(self.next) {
+            Some((offset, token)) => anyhow!("Unexpected {:?} at offset {}", token, offset),
+            None => anyhow!("Unexpected end of expression at offset {}", self.end),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Packet> {
+        let left = self.sum()?;
+        let packet_type = match self.peek() {
+            Some(Token::Less) => PacketType::LessThan,
+            Some(Token::Greater) => PacketType::GreaterThan,
+            Some(Token::Equal) => PacketType::EqualTo,
+            _ => return Ok(left),
+        };
+        self.next += 1;
+        let right = self.sum()?;
+        Ok(operator(packet_type, vec![left, right]))
+    }
+
+    fn sum(&mut self) -> Result<Packet> {
+        let mut operands = vec![self.product()?];
+        while self.peek() == Some(&Token::Plus) {
+            self.next += 1;
+            operands.push(self.product()?);
+        }
+        Ok(collapse(PacketType::Sum, operands))
+    }
+
+    fn product(&mut self) -> Result<Packet> {
+        let mut operands = vec![self.atom()?];
+        while self.peek() == Some(&Token::Star) {
+            self.next += 1;
+            operands.push(self.atom()?);
+        }
+        Ok(collapse(PacketType::Product, operands))
+    }
+
+    fn atom(&mut self) -> Result<Packet> {
+        let name = match self.peek() {
+            Some(Token::Integer(value)) => {
+                let value = *value;
+                self.next += 1;
+                return Ok(Packet::literal(0, value));
+            }
+            Some(Token::Open) => {
+                self.next += 1;
+                let packet = self.comparison()?;
+                self.expect(Token::Close)?;
+                return Ok(packet);
+            }
+            Some(Token::Name(name)) => name.clone(),
+            _ => return Err(self.unexpected()),
+        };
+        let packet_type = match name.as_str() {
+            "sum" => PacketType::Sum,
+            "product" => PacketType::Product,
+            "min" => PacketType::Minimum,
+            "max" => PacketType::Maximum,
+            "gt" => PacketType::GreaterThan,
+            "lt" => PacketType::LessThan,
+            "eq" => PacketType::EqualTo,
+            _ => {
+                let offset = self.tokens[self.next].0;
+                return Err(anyhow!("Unknown function {:?} at offset {}", name, offset));
+            }
+        };
+        self.next += 1;
+        self.expect(Token::Open)?;
+        let mut arguments = Vec::new();
+        if self.peek() != Some(&Token::Close) {
+            arguments.push(self.comparison()?);
+            while self.peek() == Some(&Token::Comma) {
+                self.next += 1;
+                arguments.push(self.comparison()?);
+            }
+        }
+        self.expect(Token::Close)?;
+        Ok(operator(packet_type, arguments))
+    }
+}
+
+fn operator(packet_type: PacketType, operands: Vec<Packet>) -> Packet {
+    Packet::operator(0, packet_type, LengthType::LengthInPackets, operands)
+}
+
+// A lone operand is just itself, not a sum or product of one thing.
+fn collapse(packet_type: PacketType, mut operands: Vec<Packet>) -> Packet {
+    if operands.len() == 1 {
+        operands.pop().unwrap()
+    } else {
+        operator(packet_type, operands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{
+        hexreader::HexReader,
+        packet::{Packet, ReadFrom},
+    };
+
+    #[test]
+    fn parse() -> Result<()> {
+        let expressions = [
+            "(3 + 5) * min(7, 9)",
+            "1 + 2 + 3",
+            "1 + (2 + 3)",
+            "(1 + 3) == (2 * 2)",
+            "max(1, 2 * 3, sum(4)) < 7",
+            "sum()",
+            "18446744073709551615",
+        ];
+        for expression in expressions {
+            assert_eq!(super::parse(expression)?.infix(), expression);
+        }
+        assert_eq!(super::parse(" 1+2*3")?.infix(), "1 + (2 * 3)");
+        assert_eq!(super::parse("gt(2,1)")?.infix(), "2 > 1");
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate() -> Result<()> {
+        assert_eq!(super::parse("(3 + 5) * min(7, 9)")?.evaluate()?, 56);
+        assert_eq!(super::parse("1 + 2 * 3 == 7")?.evaluate()?, 1);
+        assert_eq!(super::parse("product(2, 3, 4) > 25")?.evaluate()?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn encode() -> Result<()> {
+        let packet = super::parse("1 + 3 == 2 * 2")?;
+        let decoded = Packet::read_from(&mut HexReader::new(&packet.encode()?)?)?;
+        assert_eq!(decoded, packet);
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let error = |expression| super::parse(expression).unwrap_err().to_string();
+        assert_eq!(error("1 +"), "Unexpected end of expression at offset 3");
+        assert_eq!(error("1 ) 2"), "Unexpected Close at offset 2");
+        assert_eq!(error("1 = 2"), "Unexpected '=' at offset 2");
+        assert_eq!(error("avg(1)"), "Unknown function \"avg\" at offset 0");
+        assert_eq!(error("1 < 2 < 3"), "Unexpected Less at offset 6");
+        assert!(error("18446744073709551616").starts_with("Bad integer"));
+    }
+}
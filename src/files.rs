@@ -1,43 +1,285 @@
 use anyhow::Error;
 use std::{
+    fmt::Display,
     fs::File,
     io::{BufRead, BufReader},
 };
 
-#[allow(dead_code)]
 pub fn read_numbers(file_name: &str) -> Result<Vec<i32>, Error> {
     let file = std::fs::File::open(file_name)?;
     Ok(std::io::BufReader::new(file)
         .lines()
-        .filter_map(|line_result| line_result.ok())
+        .map_while(Result::ok)
         .filter_map(|line| line.trim().parse::<i32>().ok())
         .collect())
 }
 
-#[allow(dead_code)]
 pub fn read_lines(file_name: &str) -> Result<Vec<String>, Error> {
     let file = std::fs::File::open(file_name)?;
     Ok(std::io::BufReader::new(file)
         .lines()
-        .filter_map(|line_result| line_result.ok())
+        .map_while(Result::ok)
         .collect())
 }
 
-#[allow(dead_code)]
 pub fn read_string(file_name: &str) -> Result<String, Error> {
     Ok(std::fs::read_to_string(file_name)?)
 }
 
-#[allow(dead_code)]
 pub fn read_numbers_one_line(file_name: &str) -> Result<Vec<u32>, Error> {
-    Ok(std::fs::read_to_string(file_name)?
-        .split(',')
-        .filter_map(|x| x.parse::<u32>().ok())
-        .collect())
+    Ok(parse_numbers_one_line(&std::fs::read_to_string(file_name)?))
 }
 
-#[allow(dead_code)]
 pub fn buf_reader(file_name: &str) -> Result<BufReader<File>, Error> {
     let file = std::fs::File::open(file_name)?;
     Ok(BufReader::new(file))
 }
+
+pub fn parse_numbers(input: &str) -> Vec<i32> {
+    input
+        .lines()
+        .filter_map(|line| line.trim().parse::<i32>().ok())
+        .collect()
+}
+
+pub fn parse_lines(input: &str) -> Vec<String> {
+    input.lines().map(|line| line.to_owned()).collect()
+}
+
+pub fn parse_numbers_one_line(input: &str) -> Vec<u32> {
+    input
+        .split(',')
+        .filter_map(|x| x.trim().parse::<u32>().ok())
+        .collect()
+}
+
+/// Where and why an input couldn't be parsed. Lines and columns start at 1.
+#[derive(Debug, PartialEq, Eq)]
+pub struct InputError {
+    pub file_name: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl InputError {
+    pub fn new(line: usize, column: usize, text: &str, reason: &str) -> InputError {
+        InputError {
+            file_name: None,
+            line,
+            column,
+            text: text.to_owned(),
+            reason: reason.to_owned(),
+        }
+    }
+
+    pub fn in_file(self, file_name: &str) -> InputError {
+        InputError {
+            file_name: Some(file_name.to_owned()),
+            ..self
+        }
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}: {:?}",
+            self.file_name.as_deref().unwrap_or("<input>"),
+            self.line,
+            self.column,
+            self.reason,
+            self.text
+        )
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Converts `\r\n` line endings to `\n` and drops trailing newlines, so a
+/// downloaded input looks like the ones in `inputs/`.
+pub fn normalise(input: &str) -> String {
+    input
+        .replace("\r\n", "\n")
+        .trim_end_matches('\n')
+        .to_owned()
+}
+
+/// Checks that an input is non-empty and already normalised.
+pub fn validate(input: &str) -> Result<(), InputError> {
+    if input.trim().is_empty() {
+        return Err(InputError::new(1, 1, "", "Input is empty"));
+    }
+    for (i, line) in input.split('\n').enumerate() {
+        if let Some(column) = line.find('\r') {
+            let column = line[..column].chars().count() + 1;
+            return Err(InputError::new(
+                i + 1,
+                column,
+                line,
+                "Expected \\n line endings",
+            ));
+        }
+    }
+    if input.ends_with('\n') {
+        let line = input.lines().count() + 1;
+        return Err(InputError::new(line, 1, "", "Unexpected trailing newline"));
+    }
+    Ok(())
+}
+
+pub fn read_numbers_strict(file_name: &str) -> Result<Vec<i32>, Error> {
+    let input = read_lines_strict(file_name)?.join("\n");
+    Ok(parse_numbers_strict(&input).map_err(|e| e.in_file(file_name))?)
+}
+
+// Unlike `read_lines`, a line that isn't valid UTF-8 is an error instead of the end of the file.
+pub fn read_lines_strict(file_name: &str) -> Result<Vec<String>, Error> {
+    let bytes = std::fs::read(file_name)?;
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(&bytes);
+    if bytes.is_empty() {
+        return Ok(Vec::new());
+    }
+    bytes
+        .split(|b| *b == b'\n')
+        .enumerate()
+        .map(|(i, line)| {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            String::from_utf8(line.to_vec()).map_err(|e| {
+                let valid = String::from_utf8_lossy(&line[..e.utf8_error().valid_up_to()]);
+                let text = String::from_utf8_lossy(line);
+                InputError::new(i + 1, valid.chars().count() + 1, &text, "Invalid UTF-8")
+                    .in_file(file_name)
+                    .into()
+            })
+        })
+        .collect()
+}
+
+pub fn read_numbers_one_line_strict(file_name: &str) -> Result<Vec<u32>, Error> {
+    let input = read_lines_strict(file_name)?.join("\n");
+    Ok(parse_numbers_one_line_strict(&input).map_err(|e| e.in_file(file_name))?)
+}
+
+pub fn parse_numbers_strict(input: &str) -> Result<Vec<i32>, InputError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let text = line.trim();
+            let column = line.len() - line.trim_start().len() + 1;
+            text.parse::<i32>()
+                .map_err(|_| InputError::new(i + 1, column, text, "Expected a number"))
+        })
+        .collect()
+}
+
+pub fn parse_numbers_one_line_strict(input: &str) -> Result<Vec<u32>, InputError> {
+    let input = input.trim_end();
+    if let Some(second_line) = input.lines().nth(1) {
+        return Err(InputError::new(2, 1, second_line, "Expected a single line"));
+    }
+
+    let mut column = 1;
+    input
+        .split(',')
+        .map(|token| {
+            let text = token.trim();
+            let token_column = column + token.len() - token.trim_start().len();
+            column += token.chars().count() + 1;
+            text.parse::<u32>()
+                .map_err(|_| InputError::new(1, token_column, text, "Expected a number"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::InputError;
+
+    #[test]
+    fn parse_numbers_strict() {
+        assert_eq!(super::parse_numbers_strict("1\n 2\n-3"), Ok(vec![1, 2, -3]));
+        assert_eq!(
+            super::parse_numbers_strict("1\n  2x\n3"),
+            Err(InputError::new(2, 3, "2x", "Expected a number"))
+        );
+        assert_eq!(
+            super::parse_numbers_strict("1\n\n3"),
+            Err(InputError::new(2, 1, "", "Expected a number"))
+        );
+    }
+
+    #[test]
+    fn parse_numbers_one_line_strict() {
+        assert_eq!(
+            super::parse_numbers_one_line_strict("3,4,3,1,2\n"),
+            Ok(vec![3, 4, 3, 1, 2])
+        );
+        assert_eq!(
+            super::parse_numbers_one_line_strict("3,4, x,1"),
+            Err(InputError::new(1, 6, "x", "Expected a number"))
+        );
+        assert_eq!(
+            super::parse_numbers_one_line_strict("3,4,,1"),
+            Err(InputError::new(1, 5, "", "Expected a number"))
+        );
+        assert_eq!(
+            super::parse_numbers_one_line_strict("3,4\n5,6"),
+            Err(InputError::new(2, 1, "5,6", "Expected a single line"))
+        );
+    }
+
+    #[test]
+    fn lenient_one_line_keeps_last_number() {
+        assert_eq!(super::parse_numbers_one_line("3,4,3\n"), vec![3, 4, 3]);
+    }
+
+    #[test]
+    fn validate() {
+        assert_eq!(super::validate("1\n2"), Ok(()));
+        assert_eq!(
+            super::validate(" \n"),
+            Err(InputError::new(1, 1, "", "Input is empty"))
+        );
+        assert_eq!(
+            super::validate("1\r\n2"),
+            Err(InputError::new(1, 2, "1\r", "Expected \\n line endings"))
+        );
+        assert_eq!(
+            super::validate("1\n2\n"),
+            Err(InputError::new(3, 1, "", "Unexpected trailing newline"))
+        );
+        assert_eq!(super::validate(&super::normalise("1\r\n2\r\n\n")), Ok(()));
+    }
+
+    #[test]
+    fn display() {
+        let error = InputError::new(2, 3, "2x", "Expected a number").in_file("inputs/day1.txt");
+        assert_eq!(
+            error.to_string(),
+            "inputs/day1.txt:2:3: Expected a number: \"2x\""
+        );
+    }
+
+    #[test]
+    fn read_strict() -> Result<()> {
+        assert_eq!(
+            super::read_numbers_strict("inputs/day1-test.txt")?,
+            super::read_numbers("inputs/day1-test.txt")?
+        );
+        assert_eq!(
+            super::read_numbers_one_line_strict("inputs/day6-test.txt")?,
+            super::read_numbers_one_line("inputs/day6-test.txt")?
+        );
+        assert_eq!(
+            super::read_lines_strict("inputs/day2-test.txt")?,
+            super::read_lines("inputs/day2-test.txt")?
+        );
+        Ok(())
+    }
+}
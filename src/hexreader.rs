@@ -1,27 +1,274 @@
+use std::{
+    fmt::Display,
+    io::{ErrorKind, Read},
+};
+
 use anyhow::{anyhow, Result};
+
 pub trait BitReader {
-    fn read_bool(&mut self) -> Result<bool>;
-    fn read_bits(&mut self, n: usize) -> Result<u16>;
+    /// Reads `n` bits, 1 to 64. A failed read leaves the reader where it was.
+    fn read_bits(&mut self, n: usize) -> Result<u64>;
+
+    /// Like `read_bits`, but the bits are still there for the next read.
+    fn peek_bits(&mut self, n: usize) -> Result<u64>;
+
+    /// The number of bits read so far.
+    fn position(&self) -> usize;
+
+    /// The number of bits left, if the reader knows where its input ends.
+    fn remaining(&self) -> Option<usize>;
+
+    /// Skips to the start of the next byte, returning the number of bits skipped.
+    fn align_to_byte(&mut self) -> usize;
+
+    fn read_bool(&mut self) -> Result<bool> {
+        Ok(self.read_bits(1)? == 1)
+    }
 }
 
+fn check_length(n: usize) -> Result<()> {
+    if n > 64 || n == 0 {
+        return Err(anyhow!("Unsupported length"));
+    }
+    Ok(())
+}
+
+/// Which end of each byte is read first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The most significant bit comes first, and the first bit read ends up
+    /// as the most significant bit of the result.
+    MsbFirst,
+    /// The least significant bit comes first, and the first bit read ends up
+    /// as the least significant bit of the result.
+    LsbFirst,
+}
+
+/// Reads bits from any byte source, pulling in bytes only as they're needed.
+pub struct StreamReader<R: Read> {
+    source: R,
+    order: BitOrder,
+    // Bits read from the source but not yet returned. With `MsbFirst` the next
+    // bit is the highest of the `buffered` low bits, with `LsbFirst` it's bit 0.
+    buffer: u128,
+    buffered: usize,
+    position: usize,
+    exhausted: bool,
+}
+
+impl<R: Read> StreamReader<R> {
+    pub fn new(source: R) -> StreamReader<R> {
+        StreamReader::with_order(source, BitOrder::MsbFirst)
+    }
+
+    pub fn with_order(source: R, order: BitOrder) -> StreamReader<R> {
+        StreamReader {
+            source,
+            order,
+            buffer: 0,
+            buffered: 0,
+            position: 0,
+            exhausted: false,
+        }
+    }
+
+    fn fill(&mut self, n: usize) -> Result<()> {
+        let mut byte = [0u8];
+        while self.buffered < n && !self.exhausted {
+            match self.source.read(&mut byte) {
+                Ok(0) => self.exhausted = true,
+                Ok(_) => {
+                    let byte = byte[0] as u128;
+                    self.buffer = match self.order {
+                        BitOrder::MsbFirst => (self.buffer << 8) | byte,
+                        BitOrder::LsbFirst => self.buffer | (byte << self.buffered),
+                    };
+                    self.buffered += 8;
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        if self.buffered < n {
+            return Err(anyhow!("Insufficient bits remaining"));
+        }
+        Ok(())
+    }
+
+    fn take(&mut self, n: usize) -> u64 {
+        let mask = |n: usize| (1u128 << n) - 1;
+        let bits = match self.order {
+            BitOrder::MsbFirst => (self.buffer >> (self.buffered - n)) & mask(n),
+            BitOrder::LsbFirst => {
+                let bits = self.buffer & mask(n);
+                self.buffer >>= n;
+                bits
+            }
+        };
+        self.buffered -= n;
+        self.buffer &= mask(self.buffered);
+        self.position += n;
+        bits as u64
+    }
+}
+
+impl<R: Read> BitReader for StreamReader<R> {
+    fn read_bits(&mut self, n: usize) -> Result<u64> {
+        check_length(n)?;
+        self.fill(n)?;
+        Ok(self.take(n))
+    }
+
+    fn peek_bits(&mut self, n: usize) -> Result<u64> {
+        check_length(n)?;
+        self.fill(n)?;
+        let (buffer, buffered, position) = (self.buffer, self.buffered, self.position);
+        let bits = self.take(n);
+        (self.buffer, self.buffered, self.position) = (buffer, buffered, position);
+        Ok(bits)
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        self.exhausted.then_some(self.buffered)
+    }
+
+    fn align_to_byte(&mut self) -> usize {
+        // Whole bytes are buffered, so the partial byte is all in the buffer.
+        let skipped = self.buffered % 8;
+        if skipped > 0 {
+            self.take(skipped);
+        }
+        skipped
+    }
+}
+
+/// A character that isn't a digit in the input's base, at a character offset
+/// counting from 0.
+#[derive(Debug, PartialEq, Eq)]
+pub struct IllegalCharacter {
+    pub offset: usize,
+    pub character: char,
+}
+
+impl Display for IllegalCharacter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Illegal character {:?} at offset {}",
+            self.character, self.offset
+        )
+    }
+}
+
+impl std::error::Error for IllegalCharacter {}
+
 pub struct HexReader {
     bytes: Vec<u8>,
+    // In bits. Less than `bytes` holds if the input didn't fill the last byte.
+    len: usize,
     byte_offset: usize,
     bit_offset: usize,
 }
 
 impl BitReader for HexReader {
-    #[allow(dead_code)]
-    fn read_bool(&mut self) -> Result<bool> {
-        Ok(self.read_bits(1)? == 1)
+    fn read_bits(&mut self, n: usize) -> Result<u64> {
+        check_length(n)?;
+        if self.remaining() < Some(n) {
+            return Err(anyhow!("Insufficient bits remaining"));
+        }
+        let mut word = 0;
+        let mut left = n;
+        while left > 0 {
+            let chunk = left.min(16);
+            word = (word << chunk) | self.read_word(chunk)? as u64;
+            left -= chunk;
+        }
+        Ok(word)
+    }
+
+    fn peek_bits(&mut self, n: usize) -> Result<u64> {
+        let (byte_offset, bit_offset) = (self.byte_offset, self.bit_offset);
+        let word = self.read_bits(n);
+        (self.byte_offset, self.bit_offset) = (byte_offset, bit_offset);
+        word
+    }
+
+    fn position(&self) -> usize {
+        self.byte_offset * 8 + self.bit_offset
     }
 
-    #[allow(dead_code)]
-    fn read_bits(&mut self, n: usize) -> Result<u16> {
-        // Only up to 16 bits supported
-        if n > 16 || n == 0 {
-            return Err(anyhow!("Unsupported length"));
+    fn remaining(&self) -> Option<usize> {
+        Some(self.len - self.position())
+    }
+
+    fn align_to_byte(&mut self) -> usize {
+        let position = self.position();
+        // Input that ends part way through a byte ends there, not at the boundary.
+        let aligned = position.next_multiple_of(8).min(self.len.max(position));
+        self.byte_offset = aligned / 8;
+        self.bit_offset = aligned % 8;
+        aligned - position
+    }
+}
+
+impl HexReader {
+    /// Decodes hex digits in either case, skipping whitespace.
+    pub fn new(input: &str) -> Result<HexReader, IllegalCharacter> {
+        let nibbles = HexReader::digits(input, 16)?;
+        let len = nibbles.len() * 4;
+        Ok(HexReader::with_len(
+            HexReader::nibbles_to_bytes(nibbles),
+            len,
+        ))
+    }
+
+    /// Decodes a string of `0`s and `1`s, skipping whitespace.
+    pub fn from_binary(input: &str) -> Result<HexReader, IllegalCharacter> {
+        let bits = HexReader::digits(input, 2)?;
+        let bytes = bits
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |byte, (i, bit)| byte | bit << (7 - i))
+            })
+            .collect();
+        Ok(HexReader::with_len(bytes, bits.len()))
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> HexReader {
+        let len = bytes.len() * 8;
+        HexReader::with_len(bytes, len)
+    }
+
+    fn with_len(bytes: Vec<u8>, len: usize) -> HexReader {
+        HexReader {
+            bytes,
+            len,
+            byte_offset: 0,
+            bit_offset: 0,
         }
+    }
+
+    fn digits(input: &str, radix: u32) -> Result<Vec<u8>, IllegalCharacter> {
+        input
+            .chars()
+            .enumerate()
+            .filter(|(_, character)| !character.is_whitespace())
+            .map(|(offset, character)| match character.to_digit(radix) {
+                Some(digit) => Ok(digit as u8),
+                None => Err(IllegalCharacter { offset, character }),
+            })
+            .collect()
+    }
+
+    // Reads up to 16 bits.
+    fn read_word(&mut self, n: usize) -> Result<u16> {
         // Nothing left to read
         if self.byte_offset >= self.bytes.len() {
             return Err(anyhow!("Reader empty"));
@@ -72,51 +319,10 @@ impl BitReader for HexReader {
         self.bit_offset = (self.bit_offset + n) % 8;
         Ok(first | second | third)
     }
-}
-
-impl HexReader {
-    #[allow(dead_code)]
-    pub fn new(input: &str) -> HexReader {
-        let nibbles = input
-            .chars()
-            .map(Self::map_char_to_bits)
-            .collect::<Vec<u8>>();
-        let bytes = HexReader::nibbles_to_bytes(nibbles);
-        let byte_offset = 0;
-        let bit_offset = 0;
-
-        HexReader {
-            bytes,
-            byte_offset,
-            bit_offset,
-        }
-    }
-
-    fn map_char_to_bits(c: char) -> u8 {
-        match c {
-            '0' => 0b0000,
-            '1' => 0b0001,
-            '2' => 0b0010,
-            '3' => 0b0011,
-            '4' => 0b0100,
-            '5' => 0b0101,
-            '6' => 0b0110,
-            '7' => 0b0111,
-            '8' => 0b1000,
-            '9' => 0b1001,
-            'A' => 0b1010,
-            'B' => 0b1011,
-            'C' => 0b1100,
-            'D' => 0b1101,
-            'E' => 0b1110,
-            'F' => 0b1111,
-            _ => unreachable!("Illegal character"),
-        }
-    }
 
     fn nibbles_to_bytes(nibbles: Vec<u8>) -> Vec<u8> {
         let mut result = Vec::new();
-        let n = if nibbles.len() % 2 == 0 {
+        let n = if nibbles.len().is_multiple_of(2) {
             nibbles.len()
         } else {
             nibbles.len() + 1
@@ -126,7 +332,7 @@ impl HexReader {
             let lower = nibbles.get(i + 1);
             let byte: u8 = match (higher, lower) {
                 (Some(higher), Some(lower)) => (higher << 4) + lower,
-                (Some(higher), None) => (higher << 4),
+                (Some(higher), None) => higher << 4,
                 (_, _) => unreachable!("Impossible"),
             };
             result.push(byte)
@@ -176,34 +382,124 @@ impl HexReader {
     }
 }
 
+pub trait BitWriter {
+    /// Writes the low `n` bits of `value`, 1 to 64. Fails if `value` doesn't
+    /// fit in `n` bits.
+    fn write_bits(&mut self, value: u64, n: usize) -> Result<()>;
+
+    /// The number of bits written so far.
+    fn position(&self) -> usize;
+
+    fn write_bool(&mut self, value: bool) -> Result<()> {
+        self.write_bits(value as u64, 1)
+    }
+}
+
+/// Collects bits MSB-first, to be read back as bytes or as hex.
+#[derive(Debug, Default)]
+pub struct HexWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter for HexWriter {
+    fn write_bits(&mut self, value: u64, n: usize) -> Result<()> {
+        check_length(n)?;
+        if n < 64 && value >> n != 0 {
+            return Err(anyhow!("{} doesn't fit in {} bits", value, n));
+        }
+        for i in (0..n).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = ((value >> i) & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (7 - self.len % 8);
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> usize {
+        self.len
+    }
+}
+
+impl HexWriter {
+    pub fn new() -> HexWriter {
+        HexWriter::default()
+    }
+
+    /// Upper case hex, with the last digit padded with zeroes.
+    pub fn to_hex(&self) -> String {
+        self.bytes
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect::<String>()
+            .chars()
+            .take(self.len.div_ceil(4))
+            .collect()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{BitReader, HexReader};
+    use super::{
+        BitOrder, BitReader, BitWriter, HexReader, HexWriter, IllegalCharacter, StreamReader,
+    };
 
     #[test]
     fn test_construct() {
-        let reader = HexReader::new("D2FE28");
+        let reader = HexReader::new("D2FE28").unwrap();
         assert_eq!(reader.bytes, vec![0b11010010, 0b11111110, 0b00101000]);
 
-        let reader = HexReader::new("E");
+        let reader = HexReader::new("E").unwrap();
         assert_eq!(reader.bytes, vec![0b11100000]);
 
-        let reader = HexReader::new("D2E");
+        let reader = HexReader::new("D2E").unwrap();
         assert_eq!(reader.bytes, vec![0b11010010, 0b11100000]);
 
-        let reader = HexReader::new("");
+        let reader = HexReader::new("").unwrap();
         assert_eq!(reader.bytes, vec![]);
     }
 
     #[test]
-    #[should_panic(expected = "Illegal character")]
     fn test_illegal_character() {
-        HexReader::new("G");
+        assert_eq!(
+            HexReader::new("G").err(),
+            Some(IllegalCharacter {
+                offset: 0,
+                character: 'G'
+            })
+        );
+        let error = HexReader::new("d2 F\nx").err().unwrap();
+        assert_eq!(error.offset, 5);
+        assert_eq!(error.to_string(), "Illegal character 'x' at offset 5");
+        assert!(HexReader::from_binary("0102").is_err());
+    }
+
+    #[test]
+    fn test_other_inputs() {
+        let reader = HexReader::new(" d2fE\n28\n").unwrap();
+        assert_eq!(reader.bytes, vec![0b11010010, 0b11111110, 0b00101000]);
+
+        let mut reader = HexReader::from_binary("1101 0010 111").unwrap();
+        assert_eq!(reader.bytes, vec![0b11010010, 0b11100000]);
+        assert_eq!(reader.remaining(), Some(11));
+        assert_eq!(reader.read_bits(11).unwrap(), 0b11010010111);
+        assert!(reader.read_bool().is_err());
+
+        let mut reader = HexReader::from_bytes(vec![0xD2, 0xFE]);
+        assert_eq!(reader.remaining(), Some(16));
+        assert_eq!(reader.read_bits(16).unwrap(), 0xD2FE);
     }
 
     #[test]
     fn test_read_few_bits() {
-        let mut reader = HexReader::new("D2FE28");
+        let mut reader = HexReader::new("D2FE28").unwrap();
         assert_eq!(reader.bytes, vec![0b11010010, 0b11111110, 0b00101000]);
         assert_eq!(reader.read_bits(3).unwrap(), 0b110);
         assert_eq!(reader.read_bits(3).unwrap(), 0b100);
@@ -217,7 +513,7 @@ mod tests {
 
     #[test]
     fn test_read_many_bits() {
-        let mut reader = HexReader::new("D2FE28D2FE28");
+        let mut reader = HexReader::new("D2FE28D2FE28").unwrap();
         assert_eq!(
             reader.bytes,
             vec![0b11010010, 0b11111110, 0b00101000, 0b11010010, 0b11111110, 0b00101000]
@@ -231,7 +527,7 @@ mod tests {
 
     #[test]
     fn test_read_last_n_bits() {
-        let reader = HexReader::new("D2");
+        let reader = HexReader::new("D2").unwrap();
         assert_eq!(reader.bytes, vec![0b11010010]);
         assert_eq!(reader.read_last_n_bits(0, 0).unwrap(), 0b0);
         assert_eq!(reader.read_last_n_bits(0, 1).unwrap(), 0b0);
@@ -243,7 +539,7 @@ mod tests {
 
     #[test]
     fn test_read_first_n_bits() {
-        let reader = HexReader::new("D2");
+        let reader = HexReader::new("D2").unwrap();
         assert_eq!(reader.bytes, vec![0b11010010]);
         assert_eq!(reader.read_first_n_bits(0, 0).unwrap(), 0b0);
         assert_eq!(reader.read_first_n_bits(0, 1).unwrap(), 0b1);
@@ -255,7 +551,7 @@ mod tests {
 
     #[test]
     fn test_read_n_bits_with_offset() {
-        let reader = HexReader::new("D2");
+        let reader = HexReader::new("D2").unwrap();
         assert_eq!(reader.bytes, vec![0b11010010]);
         assert_eq!(reader.read_n_bits_with_offset(0, 0, 0).unwrap(), 0b0);
         assert_eq!(reader.read_n_bits_with_offset(0, 0, 4).unwrap(), 0b0);
@@ -272,14 +568,115 @@ mod tests {
 
     #[test]
     fn test_read_unsupported_lengths() {
-        let mut reader = HexReader::new("D2FE28");
+        let mut reader = HexReader::new("D2FE28").unwrap();
         assert_eq!(reader.bytes, vec![0b11010010, 0b11111110, 0b00101000]);
         assert!(reader.read_bits(0).is_err());
-        assert!(reader.read_bits(17).is_err());
+        assert!(reader.read_bits(65).is_err());
 
         // Unsupported read doesn't affect reader
         assert_eq!(reader.read_bits(8).unwrap(), 0b11010010);
         assert_eq!(reader.read_bits(16).unwrap(), 0b1111111000101000);
         assert!(reader.read_bits(8).is_err());
     }
+
+    #[test]
+    fn test_read_wide() {
+        let mut reader = HexReader::new("D2FE28D2FE28D2FE28").unwrap();
+        assert_eq!(reader.read_bits(3).unwrap(), 0b110);
+        assert_eq!(reader.read_bits(64).unwrap(), 0x97F14697F14697F1);
+        assert_eq!(reader.remaining(), Some(5));
+        assert!(reader.read_bits(6).is_err());
+        assert_eq!(reader.read_bits(5).unwrap(), 0b01000);
+    }
+
+    #[test]
+    fn test_peek_position_align() {
+        let mut reader = HexReader::new("D2FE28").unwrap();
+        assert_eq!(reader.peek_bits(3).unwrap(), 0b110);
+        assert_eq!(reader.position(), 0);
+        assert_eq!(reader.read_bits(3).unwrap(), 0b110);
+        assert_eq!(reader.position(), 3);
+        assert_eq!(reader.remaining(), Some(21));
+
+        assert_eq!(reader.align_to_byte(), 5);
+        assert_eq!(reader.align_to_byte(), 0);
+        assert_eq!(reader.read_bits(8).unwrap(), 0b11111110);
+        assert_eq!(reader.position(), 16);
+    }
+
+    #[test]
+    fn test_align_short_input() {
+        let mut reader = HexReader::from_binary("111").unwrap();
+        assert_eq!(reader.read_bits(1).unwrap(), 1);
+        assert_eq!(reader.align_to_byte(), 2);
+        assert_eq!(reader.position(), 3);
+        assert_eq!(reader.remaining(), Some(0));
+        assert!(reader.read_bits(1).is_err());
+        assert_eq!(reader.align_to_byte(), 0);
+
+        let mut reader = HexReader::new("E").unwrap();
+        assert!(reader.read_bool().unwrap());
+        assert_eq!(reader.align_to_byte(), 3);
+        assert!(reader.read_bits(1).is_err());
+        assert_eq!(reader.remaining(), Some(0));
+    }
+
+    #[test]
+    fn test_stream_msb_first() {
+        let bytes: &[u8] = &[0xD2, 0xFE, 0x28, 0xD2, 0xFE, 0x28, 0xD2, 0xFE, 0x28];
+        let mut reader = StreamReader::new(bytes);
+        assert_eq!(reader.peek_bits(3).unwrap(), 0b110);
+        assert_eq!(reader.read_bits(3).unwrap(), 0b110);
+        assert_eq!(reader.remaining(), None);
+        assert_eq!(reader.read_bits(64).unwrap(), 0x97F14697F14697F1);
+        assert_eq!(reader.position(), 67);
+
+        assert!(reader.read_bits(6).is_err());
+        assert_eq!(reader.remaining(), Some(5));
+        assert_eq!(reader.align_to_byte(), 5);
+        assert_eq!(reader.remaining(), Some(0));
+        assert!(reader.read_bool().is_err());
+    }
+
+    #[test]
+    fn test_stream_lsb_first() {
+        let bytes: &[u8] = &[0b11010010, 0b11111110];
+        let mut reader = StreamReader::with_order(bytes, BitOrder::LsbFirst);
+        assert_eq!(reader.read_bits(3).unwrap(), 0b010);
+        assert!(!reader.read_bool().unwrap());
+        assert_eq!(reader.peek_bits(8).unwrap(), 0b11101101);
+        assert_eq!(reader.align_to_byte(), 4);
+        assert_eq!(reader.read_bits(8).unwrap(), 0b11111110);
+        assert!(reader.read_bits(1).is_err());
+    }
+
+    #[test]
+    fn test_stream_matches_hex() {
+        let bytes: &[u8] = &[0x8A, 0x00, 0x4A, 0x80, 0x1A, 0x80, 0x02, 0xF4, 0x78];
+        let mut stream = StreamReader::new(bytes);
+        let mut hex = HexReader::new("8A004A801A8002F478").unwrap();
+        for n in [1, 7, 15, 11, 33, 5] {
+            assert_eq!(stream.read_bits(n).unwrap(), hex.read_bits(n).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_write() {
+        let mut writer = HexWriter::new();
+        writer.write_bits(0b110, 3).unwrap();
+        writer.write_bits(0b100, 3).unwrap();
+        writer.write_bool(true).unwrap();
+        writer.write_bits(0b01111111000101, 14).unwrap();
+        assert_eq!(writer.position(), 21);
+        assert_eq!(writer.to_hex(), "D2FE28");
+
+        assert!(writer.write_bits(4, 2).is_err());
+        assert!(writer.write_bits(0, 0).is_err());
+        writer.write_bits(u64::MAX, 64).unwrap();
+        assert_eq!(writer.position(), 85);
+
+        let mut reader = HexReader::from_bytes(writer.into_bytes());
+        assert_eq!(reader.read_bits(21).unwrap(), 0b110100101111111000101);
+        assert_eq!(reader.read_bits(64).unwrap(), u64::MAX);
+    }
 }
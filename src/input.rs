@@ -0,0 +1,215 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::cache::{self, Cache, LocalFetcher};
+
+/// Overrides the directory inputs are read from.
+pub const INPUTS_DIR_VAR: &str = "AOC2021_INPUTS";
+
+/// Where puzzle inputs come from. Inputs are named like their files, without
+/// the extension: `day16` or `day16-test`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// A single file, whatever input is asked for.
+    File(PathBuf),
+    /// Standard input, whatever input is asked for.
+    Stdin,
+    /// A directory holding `<name>.txt` files.
+    Dir(PathBuf),
+    /// A per-user cache of puzzle inputs, filled from `inputs/` on first use.
+    /// Only `day<day>` inputs can be cached.
+    Cache(PathBuf),
+    /// The files in `inputs/`, compiled into the binary.
+    #[cfg(feature = "embedded-inputs")]
+    Embedded,
+}
+
+impl Source {
+    pub fn read(&self, name: &str) -> Result<String> {
+        match self {
+            Source::File(path) => read_file(path),
+            Source::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Reading stdin")?;
+                Ok(input)
+            }
+            Source::Dir(dir) => read_file(&dir.join(format!("{}.txt", name))),
+            Source::Cache(dir) => {
+                let day = day(name).ok_or(anyhow::anyhow!("Can't cache input {}", name))?;
+                Cache::new(dir, LocalFetcher::new(inputs_dir())).get(cache::YEAR, day)
+            }
+            #[cfg(feature = "embedded-inputs")]
+            Source::Embedded => embedded::get(name)
+                .map(|input| input.to_owned())
+                .ok_or(anyhow::anyhow!("No embedded input named {}", name)),
+        }
+    }
+
+    /// Names where `name` is read from, for error messages.
+    pub fn location(&self, name: &str) -> String {
+        match self {
+            Source::File(path) => path.display().to_string(),
+            Source::Stdin => "<stdin>".to_owned(),
+            Source::Dir(dir) => dir.join(format!("{}.txt", name)).display().to_string(),
+            Source::Cache(dir) => match day(name) {
+                Some(day) => Cache::new(dir, LocalFetcher::new(inputs_dir()))
+                    .path(cache::YEAR, day)
+                    .display()
+                    .to_string(),
+                None => format!("<cached {}>", name),
+            },
+            #[cfg(feature = "embedded-inputs")]
+            Source::Embedded => format!("<embedded {}>", name),
+        }
+    }
+}
+
+impl Default for Source {
+    /// `$AOC2021_INPUTS` if set, then the embedded inputs if compiled in, then
+    /// the crate's own `inputs/` directory.
+    fn default() -> Source {
+        if let Some(dir) = std::env::var_os(INPUTS_DIR_VAR) {
+            return Source::Dir(PathBuf::from(dir));
+        }
+        #[cfg(feature = "embedded-inputs")]
+        return Source::Embedded;
+        #[cfg(not(feature = "embedded-inputs"))]
+        Source::Dir(inputs_dir())
+    }
+}
+
+pub fn name(day: u32) -> String {
+    format!("day{}", day)
+}
+
+/// The day a `day<day>` name refers to, or `None` for other inputs.
+pub fn day(name: &str) -> Option<u32> {
+    name.strip_prefix("day")?.parse().ok()
+}
+
+/// The crate's own `inputs/` directory.
+pub fn inputs_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs")
+}
+
+/// Reads an input from the default source.
+pub fn read(name: &str) -> Result<String> {
+    Source::default().read(name)
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("Reading {}", path.display()))
+}
+
+#[cfg(feature = "embedded-inputs")]
+mod embedded {
+    macro_rules! embed {
+        ($($name:literal),* $(,)?) => {
+            &[$(($name, include_str!(concat!("../inputs/", $name, ".txt")))),*]
+        };
+    }
+
+    static INPUTS: &[(&str, &str)] = embed!(
+        "day1",
+        "day1-test",
+        "day2",
+        "day2-test",
+        "day3",
+        "day3-test",
+        "day4",
+        "day4-test",
+        "day5",
+        "day5-test",
+        "day6",
+        "day6-test",
+        "day7",
+        "day7-test",
+        "day8",
+        "day8-test",
+        "day9",
+        "day9-test",
+        "day10",
+        "day10-test",
+        "day11",
+        "day11-test",
+        "day12",
+        "day12-test",
+        "day13",
+        "day13-test",
+        "day14",
+        "day14-test",
+        "day15",
+        "day15-test",
+        "day16",
+        "day16-test",
+        "day17",
+        "day17-test",
+        "day18",
+        "day19",
+        "day19-test",
+        "day20",
+        "day20-test",
+        "day21",
+        "day21-test",
+        "day22",
+        "day22-test",
+    );
+
+    pub fn get(name: &str) -> Option<&'static str> {
+        INPUTS
+            .iter()
+            .find(|(input_name, _)| *input_name == name)
+            .map(|(_, input)| *input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use anyhow::Result;
+
+    use super::Source;
+
+    #[test]
+    fn dir() -> Result<()> {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        let source = Source::Dir(dir.clone());
+        assert_eq!(source.read("day6-test")?, "3,4,3,1,2");
+        assert_eq!(
+            source.location("day6-test"),
+            dir.join("day6-test.txt").display().to_string()
+        );
+        assert!(source.read("day99").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn file() -> Result<()> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("inputs/day6-test.txt");
+        let source = Source::File(path);
+        assert_eq!(source.read("anything")?, "3,4,3,1,2");
+        Ok(())
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(super::name(16), "day16");
+        assert_eq!(super::day("day16"), Some(16));
+        assert_eq!(super::day("day16-test"), None);
+    }
+
+    #[cfg(feature = "embedded-inputs")]
+    #[test]
+    fn embedded() -> Result<()> {
+        assert_eq!(Source::Embedded.read("day6-test")?, "3,4,3,1,2");
+        assert!(Source::Embedded.read("day99").is_err());
+        Ok(())
+    }
+}
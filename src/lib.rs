@@ -0,0 +1,45 @@
+extern crate lazy_static;
+
+mod day01;
+mod day02;
+mod day03;
+mod day04;
+mod day05;
+mod day06;
+mod day07;
+mod day08;
+mod day09;
+mod day10;
+mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+mod day19;
+mod day20;
+mod day21;
+mod day22;
+
+pub mod answers;
+pub mod automaton;
+pub mod basins;
+pub mod bench;
+#[cfg(feature = "big-integers")]
+pub mod bigint;
+pub mod cache;
+pub mod cycle;
+pub mod expression;
+pub mod files;
+pub mod geometry;
+pub mod hexreader;
+pub mod input;
+pub mod matrix;
+pub mod neighbourhood;
+pub mod packet;
+pub mod registry;
+pub mod search;
+pub mod solution;
+pub mod sparse;
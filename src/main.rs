@@ -1,33 +1,257 @@
-extern crate lazy_static;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-
-mod files;
-mod hexreader;
-mod matrix;
-mod packet;
-
-fn main() {
-    println!("Hello, world!");
+use std::{collections::HashMap, time::Instant};
+
+use anyhow::{anyhow, Context, Result};
+
+use aoc2021::{
+    answers::{self, Answers},
+    bench, cache,
+    files::{self, InputError},
+    input::{self, Source},
+    registry,
+};
+
+const USAGE: &str = "Usage:
+    aoc2021 run <day> <part> [--input <path>|-] [--inputs-dir <dir>] [--cached]
+    aoc2021 verify [--answers <path>] [--inputs-dir <dir>] [--cached]
+    aoc2021 bench [<day> [<part>]] [--iterations <n>] [--format table|csv|json] [--inputs-dir <dir>] [--cached]";
+
+// Options that don't take a value.
+const FLAGS: &[&str] = &["--cached"];
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(|s| s.as_str()) {
+        Some("run") => run(&args[1..]),
+        Some("verify") => verify(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        _ => Err(anyhow!(USAGE)),
+    }
+}
+
+fn run(args: &[String]) -> Result<()> {
+    let (positional, options) = parse_args(args, &["--input", "--inputs-dir", "--cached"])?;
+    let (day, part) = match positional[..] {
+        [day, part] => (
+            day.parse::<u32>()
+                .map_err(|_| anyhow!("Day must be a number\n{}", USAGE))?,
+            part.parse::<u32>()
+                .map_err(|_| anyhow!("Part must be a number\n{}", USAGE))?,
+        ),
+        _ => return Err(anyhow!(USAGE)),
+    };
+    let source = source(&options)?;
+    let name = input::name(day);
+
+    let input = source.read(&name)?;
+    let start = Instant::now();
+    let answer = solve(day, part, &input).map_err(|e| in_file(e, &source.location(&name)))?;
+    let elapsed = start.elapsed();
+
+    if answer.contains('\n') {
+        println!("Day {} part {}:\n{}", day, part, answer);
+    } else {
+        println!("Day {} part {}: {}", day, part, answer);
+    }
+    println!("Took {:?}", elapsed);
+    Ok(())
+}
+
+fn verify(args: &[String]) -> Result<()> {
+    let (positional, options) = parse_args(args, &["--answers", "--inputs-dir", "--cached"])?;
+    if !positional.is_empty() {
+        return Err(anyhow!(USAGE));
+    }
+    let answers_path = match options.get("--answers") {
+        Some(path) => path.into(),
+        None => answers::default_path(),
+    };
+    let answers_text = files::read_string(&answers_path.to_string_lossy())
+        .with_context(|| format!("reading answers file {}", answers_path.display()))?;
+    let answers = Answers::new(&answers_text)?;
+    let source = source(&options)?;
+
+    let mut passed = 0;
+    let mut checked = 0;
+    let mut failures = Vec::new();
+    println!(
+        "{:>3}  {:>4}  {:<7}  {:>10}",
+        "Day", "Part", "Result", "Time"
+    );
+    for solution in registry::all() {
+        let day = solution.day();
+        let name = input::name(day);
+        let input = source.read(&name);
+        for part in 1..=2 {
+            let expected = match answers.get(day, part) {
+                Some(expected) => expected,
+                None => {
+                    println!("{:>3}  {:>4}  {:<7}", day, part, "skipped");
+                    continue;
+                }
+            };
+            checked += 1;
+
+            let input = match &input {
+                Ok(input) => input,
+                Err(e) => {
+                    println!("{:>3}  {:>4}  {:<7}", day, part, "ERROR");
+                    failures.push(format!("Day {} part {}: {}", day, part, e));
+                    continue;
+                }
+            };
+            let start = Instant::now();
+            let answer = solution.run(part, input);
+            let elapsed = format!("{:.2?}", start.elapsed());
+
+            let result = match answer {
+                Ok(answer) if answer == expected => {
+                    passed += 1;
+                    "pass"
+                }
+                Ok(answer) => {
+                    failures.push(format!(
+                        "Day {} part {}: expected {:?}, got {:?}",
+                        day, part, expected, answer
+                    ));
+                    "FAIL"
+                }
+                Err(e) => {
+                    let e = in_file(e, &source.location(&name));
+                    failures.push(format!("Day {} part {}: {}", day, part, e));
+                    "ERROR"
+                }
+            };
+            println!("{:>3}  {:>4}  {:<7}  {:>10}", day, part, result, elapsed);
+        }
+    }
+
+    println!();
+    for failure in &failures {
+        println!("{}", failure);
+    }
+    println!("{} of {} passed", passed, checked);
+    if passed != checked {
+        return Err(anyhow!("{} checks failed", checked - passed));
+    }
+    Ok(())
+}
+
+fn bench(args: &[String]) -> Result<()> {
+    let (positional, options) = parse_args(
+        args,
+        &["--iterations", "--format", "--inputs-dir", "--cached"],
+    )?;
+    let numbers = positional
+        .iter()
+        .map(|arg| arg.parse::<u32>())
+        .collect::<Result<Vec<u32>, _>>()
+        .map_err(|_| anyhow!("Day and part must be numbers\n{}", USAGE))?;
+    let (days, parts) = match numbers[..] {
+        [] => (registry::all().map(|s| s.day()).collect(), vec![1, 2]),
+        [day] => (vec![day], vec![1, 2]),
+        [day, part] => (vec![day], vec![part]),
+        _ => return Err(anyhow!(USAGE)),
+    };
+    let iterations = match options.get("--iterations") {
+        Some(n) => positive_number("--iterations", n)?,
+        None => 10,
+    };
+    let format = options.get("--format").copied().unwrap_or("table");
+    if !matches!(format, "table" | "csv" | "json") {
+        return Err(anyhow!("Unknown format {}\n{}", format, USAGE));
+    }
+
+    let source = source(&options)?;
+    let mut benches = Vec::new();
+    for day in days {
+        let solution = registry::get(day).ok_or(anyhow!("No solution for day {}", day))?;
+        let name = input::name(day);
+        let input = source.read(&name)?;
+        for part in &parts {
+            // Progress goes to stderr so csv and json output can be redirected cleanly.
+            eprintln!("Benchmarking day {} part {}", day, part);
+            let bench = bench::bench(solution, *part, &input, iterations)
+                .map_err(|e| in_file(e, &source.location(&name)))?;
+            benches.push(bench);
+        }
+    }
+
+    let output = match format {
+        "csv" => bench::to_csv(&benches),
+        "json" => bench::to_json(&benches),
+        _ => bench::to_table(&benches),
+    };
+    print!("{}", output);
+    Ok(())
+}
+
+// Splits arguments into positional ones and the given `--option value` pairs.
+// Flags are stored with an empty value.
+fn parse_args<'a>(
+    args: &'a [String],
+    known_options: &[&str],
+) -> Result<(Vec<&'a str>, HashMap<&'a str, &'a str>)> {
+    let mut positional = Vec::new();
+    let mut options = HashMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            positional.push(arg.as_str());
+            continue;
+        }
+        if !known_options.contains(&arg.as_str()) {
+            return Err(anyhow!("Unknown argument {}\n{}", arg, USAGE));
+        }
+        if FLAGS.contains(&arg.as_str()) {
+            options.insert(arg.as_str(), "");
+            continue;
+        }
+        let value = args
+            .next()
+            .ok_or(anyhow!("{} needs a value\n{}", arg, USAGE))?;
+        if arg == "--iterations" {
+            positive_number(arg, value)?;
+        }
+        options.insert(arg.as_str(), value.as_str());
+    }
+    Ok((positional, options))
+}
+
+fn positive_number(option: &str, value: &str) -> Result<usize> {
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(anyhow!("{} must be a positive number\n{}", option, USAGE)),
+    }
+}
+
+// `--input` wins over `--inputs-dir`, then `--cached`, then the default source.
+fn source(options: &HashMap<&str, &str>) -> Result<Source> {
+    if let Some(path) = options.get("--input") {
+        return Ok(match *path {
+            "-" => Source::Stdin,
+            path => Source::File(path.into()),
+        });
+    }
+    if let Some(dir) = options.get("--inputs-dir") {
+        return Ok(Source::Dir(dir.into()));
+    }
+    if options.contains_key("--cached") {
+        let dir = cache::default_dir().ok_or(anyhow!("Can't find a cache directory"))?;
+        return Ok(Source::Cache(dir));
+    }
+    Ok(Source::default())
+}
+
+// Parse errors don't know which file they came from, so name it here.
+fn in_file(error: anyhow::Error, file_name: &str) -> anyhow::Error {
+    match error.downcast::<InputError>() {
+        Ok(error) => error.in_file(file_name).into(),
+        Err(error) => error,
+    }
+}
+
+fn solve(day: u32, part: u32, input: &str) -> Result<String> {
+    registry::get(day)
+        .ok_or(anyhow!("No solution for day {}", day))?
+        .run(part, input)
 }
@@ -1,13 +1,27 @@
-use std::fmt::Write;
+use std::{
+    collections::HashSet,
+    fmt::{Display, Write},
+    ops::AddAssign,
+};
 
 use anyhow::{anyhow, Result};
 
-pub struct Matrix {
-    storage: Vec<u32>,
+use crate::{
+    files::InputError,
+    neighbourhood::{Offset, Topology, MOORE, VON_NEUMANN},
+};
+
+/// A rectangular grid stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    storage: Vec<T>,
     pub max_x: usize,
     pub max_y: usize,
 }
 
+/// A grid of single digits.
+pub type Matrix = Grid<u32>;
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
 pub struct Point {
     pub x: usize,
@@ -15,60 +29,99 @@ pub struct Point {
 }
 
 impl Matrix {
-    #[allow(dead_code)]
     pub fn new(input: &str) -> Result<Matrix> {
-        let storage = input
-            .split("")
-            .filter_map(|d| d.parse::<u32>().ok())
-            .collect();
-        let max_x = input
-            .split_ascii_whitespace()
-            .next()
-            .ok_or(anyhow!("Invalid input"))?
-            .split("")
-            .filter_map(|d| d.parse::<u32>().ok())
-            .count();
-        let max_y = input.split_ascii_whitespace().count();
-        Ok(Matrix {
+        Ok(Grid::parse(input, |c| c.to_digit(10))?)
+    }
+
+    pub fn construct_extended_matrix(&self) -> Matrix {
+        self.tiled(5, 5, Matrix::extended_value).materialise()
+    }
+
+    /// Each tile right or down adds 1 to the risk, wrapping from 9 back to 1.
+    /// A 0 never gets past 8, so it's never wrapped.
+    pub fn extended_value(tile: Point, value: &u32) -> u32 {
+        let value = value + tile.x as u32 + tile.y as u32;
+        if value <= 9 {
+            value
+        } else {
+            (value - 1) % 9 + 1
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn filled(max_x: usize, max_y: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            storage: vec![value; max_x * max_y],
+            max_x,
+            max_y,
+        }
+    }
+
+    /// Builds a grid from rows that must all be the same length.
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Result<Grid<T>> {
+        let mut storage = Vec::new();
+        let mut max_x = 0;
+        let mut max_y = 0;
+        for row in rows {
+            if max_y == 0 {
+                max_x = row.len();
+            } else if row.len() != max_x {
+                return Err(anyhow!(
+                    "Row {} has {} columns, expected {}",
+                    max_y,
+                    row.len(),
+                    max_x
+                ));
+            }
+            storage.extend(row);
+            max_y += 1;
+        }
+        if storage.is_empty() {
+            return Err(anyhow!("Empty grid"));
+        }
+        Ok(Grid {
             storage,
             max_x,
             max_y,
         })
     }
 
-    #[allow(dead_code)]
-    pub fn construct_extended_matrix(&self) -> Matrix {
-        let new_storage = vec![0u32; self.len() * 25];
-        let new_max_x = self.max_x * 5;
-        let new_max_y = self.max_y * 5;
-        let mut new_matrix = Matrix {
-            storage: new_storage,
-            max_x: new_max_x,
-            max_y: new_max_y,
-        };
-
-        for p in new_matrix.all_points().collect::<Vec<Point>>() {
-            let value = if p.x >= self.max_x {
-                let p = Point {
-                    x: p.x - self.max_x,
-                    y: p.y,
-                };
-                new_matrix.value(&p).unwrap() + 1
-            } else if p.y >= self.max_y {
-                let p = Point {
-                    x: p.x,
-                    y: p.y - self.max_y,
-                };
-                new_matrix.value(&p).unwrap() + 1
-            } else {
-                *self.value(&p).unwrap()
-            };
-
-            let translated_val = if value <= 9 { value } else { 1 };
-            new_matrix.set(&p, translated_val);
+    /// Parses one row per line, mapping each character to a cell. Every row
+    /// must be as wide as the first.
+    pub fn parse(input: &str, cell: impl Fn(char) -> Option<T>) -> Result<Grid<T>, InputError> {
+        let mut storage = Vec::new();
+        let mut max_x = 0;
+        let mut max_y = 0;
+        for (i, line) in input.trim_end().lines().enumerate() {
+            let line = line.trim_end();
+            let mut width = 0;
+            for (column, c) in line.chars().enumerate() {
+                let value = cell(c).ok_or_else(|| {
+                    InputError::new(i + 1, column + 1, &c.to_string(), "Unexpected character")
+                })?;
+                storage.push(value);
+                width += 1;
+            }
+            if i == 0 {
+                max_x = width;
+            } else if width != max_x {
+                let reason = format!("Expected {} columns", max_x);
+                return Err(InputError::new(i + 1, width.min(max_x) + 1, line, &reason));
+            }
+            max_y += 1;
         }
-
-        new_matrix
+        if storage.is_empty() {
+            return Err(InputError::new(1, 1, "", "Empty grid"));
+        }
+        Ok(Grid {
+            storage,
+            max_x,
+            max_y,
+        })
     }
 
     pub fn all_points(&self) -> impl Iterator<Item = Point> + '_ {
@@ -77,7 +130,10 @@ impl Matrix {
             .map(|(x, y)| Point { x, y })
     }
 
-    pub fn find(&self, needle: u32) -> impl Iterator<Item = Point> + '_ {
+    pub fn find(&self, needle: T) -> impl Iterator<Item = Point> + '_
+    where
+        T: PartialEq,
+    {
         self.all_points()
             .filter(move |point| match self.value(point) {
                 Some(val) => *val == needle,
@@ -85,53 +141,226 @@ impl Matrix {
             })
     }
 
-    pub fn value(&self, point: &Point) -> Option<&u32> {
-        self.storage.get(point.y * self.max_x + point.x)
+    pub fn value(&self, point: &Point) -> Option<&T> {
+        self.index(point).map(|i| &self.storage[i])
     }
 
-    pub fn set(&mut self, point: &Point, val: u32) {
-        self.storage[(point.y * self.max_x + point.x)] = val;
+    pub fn value_mut(&mut self, point: &Point) -> Option<&mut T> {
+        self.index(point).map(|i| &mut self.storage[i])
     }
 
-    pub fn add_to_all(&mut self, n: u32) {
-        for x in self.storage.iter_mut() {
-            *x += n;
+    pub fn set(&mut self, point: &Point, val: T) {
+        self.storage[point.y * self.max_x + point.x] = val;
+    }
+
+    /// Every cell, row by row.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.storage.iter()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.storage[y * self.max_x..(y + 1) * self.max_x]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.max_y).map(|y| self.row(y))
+    }
+
+    /// Empty if `x` is off the grid.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        (0..self.max_y).filter_map(move |y| self.value(&Point { x, y }))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.max_x).map(move |x| self.column(x))
+    }
+
+    /// A `max_x` by `max_y` window with its top left corner at `origin`, or
+    /// `None` if it doesn't fit.
+    pub fn view(&self, origin: Point, max_x: usize, max_y: usize) -> Option<GridView<'_, T>> {
+        if origin.x + max_x > self.max_x || origin.y + max_y > self.max_y {
+            return None;
+        }
+        Some(GridView {
+            grid: self,
+            origin,
+            max_x,
+            max_y,
+        })
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            storage: self.storage.iter().map(f).collect(),
+            max_x: self.max_x,
+            max_y: self.max_y,
+        }
+    }
+
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.remap(self.max_y, self.max_x, |x, y| (y, x))
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.remap(self.max_y, self.max_x, |x, y| (y, self.max_y - 1 - x))
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.remap(self.max_y, self.max_x, |x, y| (self.max_x - 1 - y, x))
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.remap(self.max_x, self.max_y, |x, y| (self.max_x - 1 - x, y))
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.remap(self.max_x, self.max_y, |x, y| (x, self.max_y - 1 - y))
+    }
+
+    /// `tiles_x` by `tiles_y` copies of this grid side by side, with each
+    /// value passed through `transform` along with the position of its tile.
+    /// Values are computed on demand, nothing is allocated until `materialise`.
+    pub fn tiled<U, F: Fn(Point, &T) -> U>(
+        &self,
+        tiles_x: usize,
+        tiles_y: usize,
+        transform: F,
+    ) -> Tiling<'_, T, F> {
+        Tiling {
+            grid: self,
+            transform,
+            max_x: self.max_x * tiles_x,
+            max_y: self.max_y * tiles_y,
+        }
+    }
+
+    /// The same as `Display`, except cells on `path` are followed by `*`
+    /// instead of a space.
+    pub fn render_path(&self, path: &[Point]) -> String
+    where
+        T: Display,
+    {
+        let on_path = path
+            .iter()
+            .filter_map(|point| self.index(point))
+            .collect::<HashSet<usize>>();
+        let mut result = String::new();
+        for (i, x) in self.storage.iter().enumerate() {
+            if i % self.max_x == 0 && i != 0 {
+                result.push('\n');
+            }
+            let marker = if on_path.contains(&i) { '*' } else { ' ' };
+            write!(result, "{}{}", x, marker).unwrap();
         }
+        result.push('\n');
+        result
     }
 
-    pub fn add(&mut self, point: &Point, n: u32) {
-        self.storage[(point.y * self.max_x + point.x)] += n;
+    /// Displays each cell as `cell` formats it, with no separators.
+    pub fn display_with<D: Display, F: Fn(&T) -> D>(&self, cell: F) -> GridDisplay<'_, T, F> {
+        GridDisplay { grid: self, cell }
     }
 
     pub fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
-        let x = point.x as i32;
-        let y = point.y as i32;
-        [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
-            .into_iter()
-            .filter(|(x, y)| *x >= 0 && *y >= 0)
-            .map(|(x, y)| (x as usize, y as usize))
-            .filter(|(x, y)| *x < self.max_x && *y < self.max_y)
-            .map(|(x, y)| Point { x, y })
+        self.neighbours_in(point, VON_NEUMANN, Topology::Bounded)
     }
 
     pub fn neighbours_with_diagonals(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
-        let x = point.x as i32;
-        let y = point.y as i32;
-        (x - 1..=x + 1)
-            .flat_map(move |x| std::iter::repeat(x).zip(y - 1..=y + 1))
-            .filter(|(x, y)| *x >= 0 && *y >= 0)
-            .map(|(x, y)| (x as usize, y as usize))
-            .filter(|(x, y)| *x < self.max_x && *y < self.max_y)
-            .map(|(x, y)| Point { x, y })
-            .filter(move |p| point != *p)
+        self.neighbours_in(point, MOORE, Topology::Bounded)
+    }
+
+    /// Adapter for `search`: each orthogonal neighbour with the cost of
+    /// stepping onto it, which is its value.
+    pub fn weighted_neighbours(&self, point: Point) -> impl Iterator<Item = (Point, T)> + '_
+    where
+        T: Copy,
+    {
+        self.neighbours(point)
+            .map(move |neighbour| (neighbour, *self.value(&neighbour).unwrap()))
+    }
+
+    /// The cells `stencil` reaches from `point`, with `topology` deciding what
+    /// happens at the edges.
+    pub fn neighbours_in<'a>(
+        &'a self,
+        point: Point,
+        stencil: &'a [Offset],
+        topology: Topology,
+    ) -> impl Iterator<Item = Point> + 'a {
+        stencil_points(point, stencil, topology, self.max_x, self.max_y)
     }
 
     pub fn len(&self) -> usize {
         self.storage.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
+    fn index(&self, point: &Point) -> Option<usize> {
+        if point.x < self.max_x && point.y < self.max_y {
+            Some(point.y * self.max_x + point.x)
+        } else {
+            None
+        }
+    }
+
+    // Builds a new grid where (x, y) takes its value from `source(x, y)` in this one.
+    fn remap(
+        &self,
+        max_x: usize,
+        max_y: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let storage = (0..max_y)
+            .flat_map(|y| (0..max_x).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (x, y) = source(x, y);
+                self.storage[y * self.max_x + x].clone()
+            })
+            .collect();
+        Grid {
+            storage,
+            max_x,
+            max_y,
+        }
+    }
 }
 
-impl std::fmt::Display for Matrix {
+impl<T: AddAssign + Copy> Grid<T> {
+    pub fn add_to_all(&mut self, n: T) {
+        for x in self.storage.iter_mut() {
+            *x += n;
+        }
+    }
+
+    pub fn add(&mut self, point: &Point, n: T) {
+        self.storage[point.y * self.max_x + point.x] += n;
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for (i, x) in self.storage.iter().enumerate() {
             if i % self.max_x == 0 && i != 0 {
@@ -144,6 +373,125 @@ impl std::fmt::Display for Matrix {
     }
 }
 
+// The cells `stencil` reaches from `point` on a `max_x` by `max_y` grid.
+fn stencil_points(
+    point: Point,
+    stencil: &[Offset],
+    topology: Topology,
+    max_x: usize,
+    max_y: usize,
+) -> impl Iterator<Item = Point> + '_ {
+    stencil.iter().filter_map(move |(dx, dy)| {
+        let x = topology.wrap(point.x as i64 + *dx as i64, max_x)?;
+        let y = topology.wrap(point.y as i64 + *dy as i64, max_y)?;
+        Some(Point { x, y })
+    })
+}
+
+/// Returned by `Grid::tiled`.
+pub struct Tiling<'a, T, F> {
+    grid: &'a Grid<T>,
+    transform: F,
+    pub max_x: usize,
+    pub max_y: usize,
+}
+
+impl<T, U, F: Fn(Point, &T) -> U> Tiling<'_, T, F> {
+    pub fn value(&self, point: &Point) -> Option<U> {
+        if point.x >= self.max_x || point.y >= self.max_y {
+            return None;
+        }
+        let tile = Point {
+            x: point.x / self.grid.max_x,
+            y: point.y / self.grid.max_y,
+        };
+        let inner = Point {
+            x: point.x % self.grid.max_x,
+            y: point.y % self.grid.max_y,
+        };
+        Some((self.transform)(tile, self.grid.value(&inner)?))
+    }
+
+    pub fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        stencil_points(
+            point,
+            VON_NEUMANN,
+            Topology::Bounded,
+            self.max_x,
+            self.max_y,
+        )
+    }
+
+    pub fn materialise(&self) -> Grid<U> {
+        let storage = (0..self.max_y)
+            .flat_map(|y| (0..self.max_x).map(move |x| Point { x, y }))
+            .map(|point| self.value(&point).unwrap())
+            .collect();
+        Grid {
+            storage,
+            max_x: self.max_x,
+            max_y: self.max_y,
+        }
+    }
+}
+
+/// A borrowed rectangle of a `Grid`. Points are relative to its top left corner.
+#[derive(Clone, Copy)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Point,
+    pub max_x: usize,
+    pub max_y: usize,
+}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn value(&self, point: &Point) -> Option<&'a T> {
+        if point.x >= self.max_x || point.y >= self.max_y {
+            return None;
+        }
+        self.grid.value(&Point {
+            x: self.origin.x + point.x,
+            y: self.origin.y + point.y,
+        })
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        (0..self.max_y).map(|y| {
+            let row = self.grid.row(self.origin.y + y);
+            &row[self.origin.x..self.origin.x + self.max_x]
+        })
+    }
+
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            storage: self.rows().flatten().cloned().collect(),
+            max_x: self.max_x,
+            max_y: self.max_y,
+        }
+    }
+}
+
+/// Returned by `Grid::display_with`.
+pub struct GridDisplay<'a, T, F> {
+    grid: &'a Grid<T>,
+    cell: F,
+}
+
+impl<T, D: Display, F: Fn(&T) -> D> Display for GridDisplay<'_, T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.grid.rows() {
+            for value in row {
+                write!(f, "{}", (self.cell)(value))?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
 impl Point {
     pub fn new(s: &str) -> Option<Point> {
         let mut parts = s.split(',');
@@ -159,7 +507,11 @@ mod tests {
 
     use anyhow::Result;
 
-    use super::{Matrix, Point};
+    use super::{Grid, Matrix, Point};
+    use crate::{
+        files::InputError,
+        neighbourhood::{self, Topology},
+    };
 
     #[test]
     fn test_add_to_all() -> Result<()> {
@@ -263,4 +615,182 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn parse() {
+        let grid = Grid::parse("#.\n.#\n", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        });
+        assert_eq!(
+            grid.map(|g| g.values().copied().collect::<Vec<bool>>()),
+            Ok(vec![true, false, false, true])
+        );
+
+        let bad_char = Grid::parse("12\n3x", |c| c.to_digit(10));
+        assert_eq!(
+            bad_char,
+            Err(InputError::new(2, 2, "x", "Unexpected character"))
+        );
+        let short_row = Grid::parse("12\n3", |c| c.to_digit(10));
+        assert_eq!(
+            short_row,
+            Err(InputError::new(2, 2, "3", "Expected 2 columns"))
+        );
+        assert!(Grid::parse("", |c| c.to_digit(10)).is_err());
+    }
+
+    #[test]
+    fn empty_grids() {
+        assert!(Grid::<u32>::from_rows(vec![vec![]]).is_err());
+        assert!(Grid::<u32>::from_rows(vec![]).is_err());
+
+        let grid = Grid::filled(0, 3, 0u32);
+        assert_eq!(grid.rows().count(), 3);
+        assert!(grid.rows().all(|row| row.is_empty()));
+        assert_eq!(grid.column(0).count(), 0);
+        assert_eq!(
+            grid.tiled(2, 2, |_, v| *v).value(&Point { x: 0, y: 0 }),
+            None
+        );
+    }
+
+    #[test]
+    fn rows_and_columns() -> Result<()> {
+        let matrix = Matrix::new("012\n345")?;
+        assert_eq!(
+            matrix.rows().collect::<Vec<&[u32]>>(),
+            [[0, 1, 2], [3, 4, 5]]
+        );
+        assert_eq!(matrix.row(1), [3, 4, 5]);
+        assert_eq!(matrix.column(1).copied().collect::<Vec<u32>>(), [1, 4]);
+        let columns = matrix
+            .columns()
+            .map(|column| column.copied().collect::<Vec<u32>>())
+            .collect::<Vec<Vec<u32>>>();
+        assert_eq!(columns, [[0, 3], [1, 4], [2, 5]]);
+        Ok(())
+    }
+
+    #[test]
+    fn view() -> Result<()> {
+        let matrix = Matrix::new("012\n345\n678")?;
+        let view = matrix.view(Point { x: 1, y: 1 }, 2, 2).unwrap();
+        assert_eq!(view.value(&Point { x: 0, y: 0 }), Some(&4));
+        assert_eq!(view.value(&Point { x: 1, y: 1 }), Some(&8));
+        assert_eq!(view.value(&Point { x: 2, y: 0 }), None);
+        assert_eq!(view.to_grid(), Matrix::new("45\n78")?);
+        assert!(matrix.view(Point { x: 2, y: 0 }, 2, 1).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn transforms() -> Result<()> {
+        let matrix = Matrix::new("012\n345")?;
+        assert_eq!(matrix.transpose(), Matrix::new("03\n14\n25")?);
+        assert_eq!(matrix.rotate_clockwise(), Matrix::new("30\n41\n52")?);
+        assert_eq!(
+            matrix.rotate_counter_clockwise(),
+            Matrix::new("25\n14\n03")?
+        );
+        assert_eq!(matrix.flip_horizontal(), Matrix::new("210\n543")?);
+        assert_eq!(matrix.flip_vertical(), Matrix::new("345\n012")?);
+        assert_eq!(matrix.rotate_clockwise().rotate_counter_clockwise(), matrix);
+        Ok(())
+    }
+
+    #[test]
+    fn display() -> Result<()> {
+        let matrix = Matrix::new("01\n23")?;
+        assert_eq!(matrix.to_string(), "0 1 \n2 3 \n");
+        let rendered = matrix.display_with(|&d| if d % 2 == 0 { '.' } else { '#' });
+        assert_eq!(rendered.to_string(), ".#\n.#\n");
+        Ok(())
+    }
+
+    #[test]
+    fn neighbours_in() -> Result<()> {
+        let matrix = Matrix::new("012\n345\n678")?;
+        let corner = Point { x: 0, y: 0 };
+        let values = |topology, stencil: &[(i32, i32)]| {
+            let mut values = matrix
+                .neighbours_in(corner, stencil, topology)
+                .map(|p| *matrix.value(&p).unwrap())
+                .collect::<Vec<u32>>();
+            values.sort_unstable();
+            values
+        };
+
+        assert_eq!(
+            values(Topology::Bounded, neighbourhood::VON_NEUMANN),
+            [1, 3]
+        );
+        assert_eq!(
+            values(Topology::Toroidal, neighbourhood::VON_NEUMANN),
+            [1, 2, 3, 6]
+        );
+        assert_eq!(
+            values(Topology::Reflective, neighbourhood::VON_NEUMANN),
+            [1, 1, 3, 3]
+        );
+        assert_eq!(
+            values(Topology::Toroidal, neighbourhood::MOORE),
+            [1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert_eq!(values(Topology::Bounded, neighbourhood::KNIGHT), [5, 7]);
+        assert_eq!(values(Topology::Bounded, neighbourhood::HEX), [1, 3]);
+        assert_eq!(
+            values(Topology::Bounded, &neighbourhood::moore(2)),
+            [1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn toroidal_narrow_grids() -> Result<()> {
+        let values = |matrix: &Matrix, point| {
+            let mut values = matrix
+                .neighbours_in(point, neighbourhood::VON_NEUMANN, Topology::Toroidal)
+                .map(|p| *matrix.value(&p).unwrap())
+                .collect::<Vec<u32>>();
+            values.sort_unstable();
+            values
+        };
+        // Up and down both wrap back onto the cell itself.
+        let row = Matrix::new("0123")?;
+        assert_eq!(values(&row, Point { x: 1, y: 0 }), [0, 1, 1, 2]);
+        // Left and right reach the same cell, as do up and down.
+        let square = Matrix::new("01\n23")?;
+        assert_eq!(values(&square, Point { x: 0, y: 0 }), [1, 1, 2, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn extended_zero() -> Result<()> {
+        let extended = Matrix::new("01\n23")?.construct_extended_matrix();
+        assert_eq!(extended.row(0), [0, 1, 1, 2, 2, 3, 3, 4, 4, 5]);
+        assert_eq!(extended.value(&Point { x: 8, y: 9 }), Some(&1));
+        assert_eq!(extended.value(&Point { x: 9, y: 9 }), Some(&2));
+        Ok(())
+    }
+
+    #[test]
+    fn tiled() -> Result<()> {
+        let matrix = Matrix::new("18\n92")?;
+        let tiling = matrix.tiled(3, 2, Matrix::extended_value);
+        assert_eq!((tiling.max_x, tiling.max_y), (6, 4));
+        assert_eq!(tiling.value(&Point { x: 2, y: 0 }), Some(2));
+        assert_eq!(tiling.value(&Point { x: 3, y: 2 }), Some(1));
+        assert_eq!(tiling.value(&Point { x: 6, y: 0 }), None);
+        assert_eq!(
+            tiling.materialise(),
+            Matrix::new("182931\n921324\n293142\n132435")?
+        );
+        assert_eq!(tiling.neighbours(Point { x: 5, y: 3 }).count(), 2);
+
+        let labels = matrix.tiled(2, 1, |tile, value| (tile.x, *value));
+        assert_eq!(labels.value(&Point { x: 3, y: 1 }), Some((1, 2)));
+        Ok(())
+    }
 }
@@ -0,0 +1,118 @@
+/// A step from a cell to one of its neighbours, as (dx, dy).
+pub type Offset = (i32, i32);
+
+/// The 4 orthogonal neighbours.
+pub const VON_NEUMANN: &[Offset] = &[(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// The 8 orthogonal and diagonal neighbours.
+pub const MOORE: &[Offset] = &[
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// The 6 neighbours of a hex grid stored in axial coordinates, where x runs
+/// along a row and y along the diagonal that leans right going down.
+pub const HEX: &[Offset] = &[(1, 0), (-1, 0), (0, 1), (0, -1), (1, -1), (-1, 1)];
+
+pub const KNIGHT: &[Offset] = &[
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
+/// Every offset within Manhattan distance `radius`.
+pub fn von_neumann(radius: i32) -> Vec<Offset> {
+    moore(radius)
+        .into_iter()
+        .filter(|(dx, dy)| dx.abs() + dy.abs() <= radius)
+        .collect()
+}
+
+/// Every offset within Chebyshev distance `radius`, in the same order as `MOORE`.
+pub fn moore(radius: i32) -> Vec<Offset> {
+    (-radius..=radius)
+        .flat_map(|dx| (-radius..=radius).map(move |dy| (dx, dy)))
+        .filter(|offset| *offset != (0, 0))
+        .collect()
+}
+
+/// What happens to neighbours that fall off the edge of the grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// They don't exist.
+    Bounded,
+    /// They wrap around to the opposite edge. Along an axis shorter than 3,
+    /// steps either way reach the same cell, or the cell itself if the axis
+    /// is 1 long, so neighbours can repeat.
+    Toroidal,
+    /// They bounce back off the edge, so -1 becomes 1. Edge cells can end up
+    /// with the same neighbour twice, or with themselves as a neighbour.
+    Reflective,
+}
+
+impl Topology {
+    /// Maps a coordinate that may be off a `size` long axis back onto it.
+    pub fn wrap(&self, coordinate: i64, size: usize) -> Option<usize> {
+        let size = size as i64;
+        if size == 0 {
+            return None;
+        }
+        let wrapped = match self {
+            Topology::Bounded => coordinate,
+            Topology::Toroidal => coordinate.rem_euclid(size),
+            Topology::Reflective if size == 1 => 0,
+            Topology::Reflective => {
+                let period = 2 * (size - 1);
+                let folded = coordinate.rem_euclid(period);
+                if folded < size {
+                    folded
+                } else {
+                    period - folded
+                }
+            }
+        };
+        (0..size).contains(&wrapped).then_some(wrapped as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Topology;
+
+    #[test]
+    fn stencils() {
+        assert_eq!(super::von_neumann(1).len(), 4);
+        assert_eq!(super::von_neumann(2).len(), 12);
+        assert_eq!(super::moore(1), super::MOORE);
+        assert_eq!(super::moore(2).len(), 24);
+    }
+
+    #[test]
+    fn wrap() {
+        assert_eq!(Topology::Bounded.wrap(-1, 3), None);
+        assert_eq!(Topology::Bounded.wrap(2, 3), Some(2));
+        assert_eq!(Topology::Bounded.wrap(3, 3), None);
+
+        assert_eq!(Topology::Toroidal.wrap(-1, 3), Some(2));
+        assert_eq!(Topology::Toroidal.wrap(3, 3), Some(0));
+        assert_eq!(Topology::Toroidal.wrap(-7, 3), Some(2));
+
+        assert_eq!(Topology::Reflective.wrap(-1, 3), Some(1));
+        assert_eq!(Topology::Reflective.wrap(3, 3), Some(1));
+        assert_eq!(Topology::Reflective.wrap(4, 3), Some(0));
+        assert_eq!(Topology::Reflective.wrap(-1, 1), Some(0));
+
+        assert_eq!(Topology::Toroidal.wrap(0, 0), None);
+    }
+}
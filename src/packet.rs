@@ -1,13 +1,81 @@
-use crate::hexreader::BitReader;
+use std::fmt::Display;
 
-use anyhow::Result;
+#[cfg(feature = "big-integers")]
+use crate::bigint::BigUint;
+use crate::hexreader::{BitReader, BitWriter, HexWriter};
+
+use anyhow::{anyhow, Result};
 
 pub trait ReadFrom<T: BitReader> {
-    fn read_from(reader: &mut T) -> Result<Self>
+    fn read_from(reader: &mut T) -> Result<Self, DecodeError>
     where
         Self: Sized;
 }
 
+pub trait WriteTo<T: BitWriter> {
+    fn write_to(&self, writer: &mut T) -> Result<()>;
+}
+
+/// Where and why a transmission couldn't be decoded.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    /// The bit the problem starts at, counting from the start of the reader.
+    pub offset: usize,
+    pub field: PacketField,
+    pub reason: DecodeReason,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PacketField {
+    Version,
+    PacketType,
+    LengthType,
+    SubPacketLength,
+    SubPacketCount,
+    LiteralGroup,
+    SubPackets,
+    Padding,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeReason {
+    /// The transmission ended part way through the field.
+    Truncated,
+    /// The sub-packets ran past the length their operator gave them.
+    LengthOverrun { expected: usize, found: usize },
+    /// There were 1 bits after the last packet.
+    NonZeroPadding,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self.reason {
+            DecodeReason::Truncated => "truncated".to_owned(),
+            DecodeReason::LengthOverrun { expected, found } => {
+                format!("{} bits long, expected {}", found, expected)
+            }
+            DecodeReason::NonZeroPadding => "non-zero".to_owned(),
+        };
+        write!(f, "{:?} at bit {}: {}", self.field, self.offset, reason)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+// Reads one field, 1 to 64 bits, recording where it started if it's cut short.
+fn read_field<T: BitReader>(
+    reader: &mut T,
+    n: usize,
+    field: PacketField,
+) -> Result<u64, DecodeError> {
+    let offset = reader.position();
+    reader.read_bits(n).map_err(|_| DecodeError {
+        offset,
+        field,
+        reason: DecodeReason::Truncated,
+    })
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Packet {
     pub header: PacketHeader,
@@ -15,7 +83,7 @@ pub struct Packet {
 }
 
 impl<T: BitReader> ReadFrom<T> for Packet {
-    fn read_from(reader: &mut T) -> Result<Self> {
+    fn read_from(reader: &mut T) -> Result<Self, DecodeError> {
         let header = PacketHeader::read_from(reader)?;
         let content = PacketContent::read_from(&header.packet_type, reader)?;
 
@@ -23,51 +91,323 @@ impl<T: BitReader> ReadFrom<T> for Packet {
     }
 }
 
+impl<T: BitWriter> WriteTo<T> for Packet {
+    fn write_to(&self, writer: &mut T) -> Result<()> {
+        let is_literal = matches!(self.content, PacketContent::Literal(_));
+        if is_literal != (self.header.packet_type == PacketType::Literal) {
+            return Err(anyhow!(
+                "{:?} packet has the wrong kind of content",
+                self.header.packet_type
+            ));
+        }
+        self.header.write_to(writer)?;
+        self.content.write_to(writer)
+    }
+}
+
 impl Packet {
+    /// Reads a whole transmission: one packet, then nothing but 0 bits.
+    pub fn decode<T: BitReader>(reader: &mut T) -> Result<Packet, DecodeError> {
+        let packet = Packet::read_from(reader)?;
+        loop {
+            let n = match reader.remaining() {
+                Some(0) => break,
+                Some(remaining) => remaining.min(64),
+                None => 1,
+            };
+            let offset = reader.position();
+            match reader.read_bits(n) {
+                Ok(0) => {}
+                Ok(bits) => {
+                    return Err(DecodeError {
+                        offset: offset + bits.leading_zeros() as usize + n - 64,
+                        field: PacketField::Padding,
+                        reason: DecodeReason::NonZeroPadding,
+                    })
+                }
+                // A stream that has run dry.
+                Err(_) => break,
+            }
+        }
+        Ok(packet)
+    }
+
+    pub fn literal(version: u16, value: u64) -> Packet {
+        Packet {
+            header: PacketHeader {
+                version: Version(version),
+                packet_type: PacketType::Literal,
+            },
+            content: PacketContent::Literal(LiteralValue::new(value)),
+        }
+    }
+
+    /// An operator packet, with its sub-packets' length given the way `length_type` says.
+    pub fn operator(
+        version: u16,
+        packet_type: PacketType,
+        length_type: LengthType,
+        sub_packets: Vec<Packet>,
+    ) -> Packet {
+        let content = match length_type {
+            LengthType::LengthInBits => PacketContent::SubPacketsInBits(sub_packets),
+            LengthType::LengthInPackets => PacketContent::NSubPackets(sub_packets),
+        };
+        Packet {
+            header: PacketHeader {
+                version: Version(version),
+                packet_type,
+            },
+            content,
+        }
+    }
+
+    /// The hex transmission for this packet, without any padding beyond the last hex digit.
+    pub fn encode(&self) -> Result<String> {
+        let mut writer = HexWriter::new();
+        self.write_to(&mut writer)?;
+        Ok(writer.to_hex())
+    }
+
     fn len(&self) -> usize {
         PacketHeader::len() + self.content.len()
     }
 
-    #[allow(dead_code)]
-    pub fn evaluate(&self) -> u64 {
-        let sub_packets: Vec<u64> = match &self.content {
-            PacketContent::Literal(LiteralValue { value, len: _ }) => return *value,
-            PacketContent::SubPacketsInBits(packets) => {
-                packets.iter().map(Packet::evaluate).collect()
+    /// The sub-packets of an operator, or nothing for a literal.
+    pub fn operands(&self) -> &[Packet] {
+        match &self.content {
+            PacketContent::Literal(_) => &[],
+            PacketContent::SubPacketsInBits(packets) | PacketContent::NSubPackets(packets) => {
+                packets
+            }
+        }
+    }
+
+    /// Reads like arithmetic, e.g. `(3 + 5) * min(7, 9)`. Nested operators
+    /// are always bracketed, so the tree's shape is visible. Sums, products
+    /// and comparisons with an unexpected number of operands are written as
+    /// calls, like `sum(1)`.
+    pub fn infix(&self) -> String {
+        if let PacketContent::Literal(literal) = &self.content {
+            return literal.to_string();
+        }
+        let operands = self.operands();
+        let packet_type = &self.header.packet_type;
+        let is_infix = match packet_type {
+            PacketType::Sum | PacketType::Product => operands.len() >= 2,
+            PacketType::GreaterThan | PacketType::LessThan | PacketType::EqualTo => {
+                operands.len() == 2
             }
-            PacketContent::NSubPackets(packets) => packets.iter().map(Packet::evaluate).collect(),
+            _ => false,
         };
-        match self.header.packet_type {
-            PacketType::Sum => sub_packets.iter().sum(),
-            PacketType::Product => sub_packets.iter().product(),
-            PacketType::Minimum => *sub_packets.iter().min().unwrap(),
-            PacketType::Maximum => *sub_packets.iter().max().unwrap(),
-            PacketType::Literal => unreachable!("Literal should have been evaluated earlier"),
-            PacketType::GreaterThan => {
-                if sub_packets[0] > sub_packets[1] {
-                    1
-                } else {
-                    0
+        if !is_infix {
+            let arguments = operands.iter().map(Packet::infix).collect::<Vec<String>>();
+            return format!("{}({})", packet_type.name(), arguments.join(", "));
+        }
+
+        let operands = operands
+            .iter()
+            .map(|operand| match operand.header.packet_type {
+                PacketType::Sum
+                | PacketType::Product
+                | PacketType::GreaterThan
+                | PacketType::LessThan
+                | PacketType::EqualTo
+                    if operand.operands().len() >= 2 =>
+                {
+                    format!("({})", operand.infix())
                 }
+                _ => operand.infix(),
+            })
+            .collect::<Vec<String>>();
+        operands.join(&format!(" {} ", packet_type.symbol()))
+    }
+
+    /// E.g. `(* (+ 3 5) (min 7 9))`.
+    pub fn sexpr(&self) -> String {
+        if let PacketContent::Literal(literal) = &self.content {
+            return literal.to_string();
+        }
+        let mut result = format!("({}", self.header.packet_type.symbol());
+        for operand in self.operands() {
+            result.push(' ');
+            result.push_str(&operand.sexpr());
+        }
+        result.push(')');
+        result
+    }
+
+    /// One line per packet, indented by depth, with its version, the bit
+    /// offset it starts at, how its sub-packets were counted and its length.
+    pub fn tree(&self) -> String {
+        let mut result = String::new();
+        self.tree_lines(0, 0, &mut result);
+        result
+    }
+
+    fn tree_lines(&self, depth: usize, offset: usize, result: &mut String) {
+        let description = match &self.content {
+            PacketContent::Literal(literal) => literal.to_string(),
+            PacketContent::SubPacketsInBits(packets) => {
+                format!("length {}", packets.iter().map(Packet::len).sum::<usize>())
             }
-            PacketType::LessThan => {
-                if sub_packets[0] < sub_packets[1] {
-                    1
-                } else {
-                    0
-                }
+            PacketContent::NSubPackets(packets) => format!("count {}", packets.len()),
+        };
+        result.push_str(&format!(
+            "{:indent$}@{} v{} {:?} {} ({} bits)\n",
+            "",
+            offset,
+            self.header.version.inner(),
+            self.header.packet_type,
+            description,
+            self.len(),
+            indent = depth * 2
+        ));
+
+        let mut offset = offset + PacketHeader::len();
+        offset += match self.content {
+            PacketContent::Literal(_) => 0,
+            PacketContent::SubPacketsInBits(_) => LengthType::len() + SubPacketLengthInBits::len(),
+            PacketContent::NSubPackets(_) => LengthType::len() + NumSubPackets::len(),
+        };
+        for operand in self.operands() {
+            operand.tree_lines(depth + 1, offset, result);
+            offset += operand.len();
+        }
+    }
+
+    pub fn evaluate(&self) -> Result<u64, EvaluationError> {
+        self.evaluate_as()
+    }
+
+    /// Evaluates with any `Number` type, so with the `big-integers` feature
+    /// `evaluate_as::<BigUint>()` never overflows.
+    pub fn evaluate_as<N: Number>(&self) -> Result<N, EvaluationError> {
+        let packet_type = self.header.packet_type;
+        if let PacketContent::Literal(literal) = &self.content {
+            return N::from_literal(literal).ok_or(EvaluationError::Overflow(packet_type));
+        }
+        let operands = self
+            .operands()
+            .iter()
+            .map(Packet::evaluate_as)
+            .collect::<Result<Vec<N>, EvaluationError>>()?;
+
+        match packet_type {
+            PacketType::Sum | PacketType::Product | PacketType::Minimum | PacketType::Maximum => {
+                let overflow = || EvaluationError::Overflow(packet_type);
+                let mut operands = operands.into_iter();
+                let first = operands.next().ok_or(EvaluationError::Empty(packet_type))?;
+                operands.try_fold(first, |result, operand| match packet_type {
+                    PacketType::Sum => result.checked_add(&operand).ok_or_else(overflow),
+                    PacketType::Product => result.checked_mul(&operand).ok_or_else(overflow),
+                    PacketType::Minimum => Ok(result.min(operand)),
+                    _ => Ok(result.max(operand)),
+                })
             }
-            PacketType::EqualTo => {
-                if sub_packets[0] == sub_packets[1] {
-                    1
-                } else {
-                    0
-                }
+            PacketType::GreaterThan | PacketType::LessThan | PacketType::EqualTo => {
+                let [left, right] =
+                    <[N; 2]>::try_from(operands).map_err(|operands| EvaluationError::Arity {
+                        packet_type,
+                        expected: 2,
+                        found: operands.len(),
+                    })?;
+                Ok(N::from_bool(match packet_type {
+                    PacketType::GreaterThan => left > right,
+                    PacketType::LessThan => left < right,
+                    _ => left == right,
+                }))
             }
+            // A literal header on an operator's content.
+            PacketType::Literal => Err(EvaluationError::Arity {
+                packet_type,
+                expected: 0,
+                found: operands.len(),
+            }),
+        }
+    }
+}
+
+/// Why a packet couldn't be evaluated.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EvaluationError {
+    /// A comparison without exactly two operands.
+    Arity {
+        packet_type: PacketType,
+        expected: usize,
+        found: usize,
+    },
+    /// A sum, product, minimum or maximum with no operands.
+    Empty(PacketType),
+    /// A result, or a literal, too big for the number type.
+    Overflow(PacketType),
+}
+
+impl Display for EvaluationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvaluationError::Arity {
+                packet_type,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{:?} expects {} operands, found {}",
+                packet_type, expected, found
+            ),
+            EvaluationError::Empty(packet_type) => write!(f, "{:?} has no operands", packet_type),
+            EvaluationError::Overflow(packet_type) => write!(f, "{:?} overflowed", packet_type),
         }
     }
 }
 
+impl std::error::Error for EvaluationError {}
+
+/// The numbers packets can be evaluated as.
+pub trait Number: Ord + Sized {
+    fn from_literal(literal: &LiteralValue) -> Option<Self>;
+    fn from_bool(value: bool) -> Self;
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+}
+
+impl Number for u64 {
+    fn from_literal(literal: &LiteralValue) -> Option<Self> {
+        literal.value()
+    }
+
+    fn from_bool(value: bool) -> Self {
+        value as u64
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        u64::checked_add(*self, *other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        u64::checked_mul(*self, *other)
+    }
+}
+
+#[cfg(feature = "big-integers")]
+impl Number for BigUint {
+    fn from_literal(literal: &LiteralValue) -> Option<Self> {
+        Some(BigUint::from_nibbles(&literal.nibbles))
+    }
+
+    fn from_bool(value: bool) -> Self {
+        BigUint::from(value as u64)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self.add(other))
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self.mul(other))
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct PacketHeader {
     pub version: Version,
@@ -75,7 +415,7 @@ pub struct PacketHeader {
 }
 
 impl<T: BitReader> ReadFrom<T> for PacketHeader {
-    fn read_from(reader: &mut T) -> Result<Self> {
+    fn read_from(reader: &mut T) -> Result<Self, DecodeError> {
         let version = Version::read_from(reader)?;
         let packet_type = PacketType::read_from(reader)?;
         Ok(PacketHeader {
@@ -85,6 +425,13 @@ impl<T: BitReader> ReadFrom<T> for PacketHeader {
     }
 }
 
+impl<T: BitWriter> WriteTo<T> for PacketHeader {
+    fn write_to(&self, writer: &mut T) -> Result<()> {
+        self.version.write_to(writer)?;
+        self.packet_type.write_to(writer)
+    }
+}
+
 impl PacketHeader {
     fn len() -> usize {
         Version::len() + PacketType::len()
@@ -95,8 +442,15 @@ impl PacketHeader {
 pub struct Version(u16);
 
 impl<T: BitReader> ReadFrom<T> for Version {
-    fn read_from(reader: &mut T) -> Result<Self> {
-        Ok(Version(reader.read_bits(Version::len())?))
+    fn read_from(reader: &mut T) -> Result<Self, DecodeError> {
+        let version = read_field(reader, Version::len(), PacketField::Version)?;
+        Ok(Version(version as u16))
+    }
+}
+
+impl<T: BitWriter> WriteTo<T> for Version {
+    fn write_to(&self, writer: &mut T) -> Result<()> {
+        writer.write_bits(self.0 as u64, Version::len())
     }
 }
 
@@ -110,7 +464,7 @@ impl Version {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum PacketType {
     Sum,
     Product,
@@ -123,8 +477,8 @@ pub enum PacketType {
 }
 
 impl<T: BitReader> ReadFrom<T> for PacketType {
-    fn read_from(reader: &mut T) -> Result<Self> {
-        let packet_type = match reader.read_bits(PacketType::len())? {
+    fn read_from(reader: &mut T) -> Result<Self, DecodeError> {
+        let packet_type = match read_field(reader, PacketType::len(), PacketField::PacketType)? {
             0 => PacketType::Sum,
             1 => PacketType::Product,
             2 => PacketType::Minimum,
@@ -132,17 +486,57 @@ impl<T: BitReader> ReadFrom<T> for PacketType {
             4 => PacketType::Literal,
             5 => PacketType::GreaterThan,
             6 => PacketType::LessThan,
-            7 => PacketType::EqualTo,
-            _ => unreachable!("Invalid operator"),
+            // Three bits can't be anything else.
+            _ => PacketType::EqualTo,
         };
         Ok(packet_type)
     }
 }
 
+impl<T: BitWriter> WriteTo<T> for PacketType {
+    fn write_to(&self, writer: &mut T) -> Result<()> {
+        let id = match self {
+            PacketType::Sum => 0,
+            PacketType::Product => 1,
+            PacketType::Minimum => 2,
+            PacketType::Maximum => 3,
+            PacketType::Literal => 4,
+            PacketType::GreaterThan => 5,
+            PacketType::LessThan => 6,
+            PacketType::EqualTo => 7,
+        };
+        writer.write_bits(id, PacketType::len())
+    }
+}
+
 impl PacketType {
     fn len() -> usize {
         3
     }
+
+    fn name(&self) -> &'static str {
+        match self {
+            PacketType::Sum => "sum",
+            PacketType::Product => "product",
+            PacketType::Minimum => "min",
+            PacketType::Maximum => "max",
+            PacketType::Literal => "literal",
+            PacketType::GreaterThan => "gt",
+            PacketType::LessThan => "lt",
+            PacketType::EqualTo => "eq",
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            PacketType::Sum => "+",
+            PacketType::Product => "*",
+            PacketType::GreaterThan => ">",
+            PacketType::LessThan => "<",
+            PacketType::EqualTo => "==",
+            _ => self.name(),
+        }
+    }
 }
 
 #[derive(Debug, Eq)]
@@ -171,7 +565,10 @@ impl PacketContent {
 
     // It's a hack. Ideally I would like to implement the trait for uniformity.
     // Or I move this code to Packet
-    fn read_from<T: BitReader>(packet_type: &PacketType, reader: &mut T) -> Result<Self> {
+    fn read_from<T: BitReader>(
+        packet_type: &PacketType,
+        reader: &mut T,
+    ) -> Result<Self, DecodeError> {
         if packet_type == &PacketType::Literal {
             return Ok(PacketContent::Literal(LiteralValue::read_from(reader)?));
         }
@@ -180,12 +577,18 @@ impl PacketContent {
         let mut sub_packets = Vec::new();
         match length_type {
             LengthType::LengthInBits => {
-                let expected_length = SubPacketLengthInBits::read_from(reader)?;
-                let mut length_so_far = 0;
-                while length_so_far < expected_length.0 {
-                    let sub_packet = Packet::read_from(reader)?;
-                    length_so_far += sub_packet.len() as u16;
-                    sub_packets.push(sub_packet);
+                let expected = SubPacketLengthInBits::read_from(reader)?.0 as usize;
+                let start = reader.position();
+                while reader.position() - start < expected {
+                    sub_packets.push(Packet::read_from(reader)?);
+                }
+                let found = reader.position() - start;
+                if found != expected {
+                    return Err(DecodeError {
+                        offset: start,
+                        field: PacketField::SubPackets,
+                        reason: DecodeReason::LengthOverrun { expected, found },
+                    });
                 }
                 Ok(PacketContent::SubPacketsInBits(sub_packets))
             }
@@ -200,6 +603,29 @@ impl PacketContent {
     }
 }
 
+impl<T: BitWriter> WriteTo<T> for PacketContent {
+    fn write_to(&self, writer: &mut T) -> Result<()> {
+        let sub_packets = match self {
+            PacketContent::Literal(literal) => return literal.write_to(writer),
+            PacketContent::SubPacketsInBits(packets) => {
+                LengthType::LengthInBits.write_to(writer)?;
+                let length = packets.iter().map(Packet::len).sum::<usize>();
+                SubPacketLengthInBits(u16::try_from(length)?).write_to(writer)?;
+                packets
+            }
+            PacketContent::NSubPackets(packets) => {
+                LengthType::LengthInPackets.write_to(writer)?;
+                NumSubPackets(u16::try_from(packets.len())?).write_to(writer)?;
+                packets
+            }
+        };
+        for packet in sub_packets {
+            packet.write_to(writer)?;
+        }
+        Ok(())
+    }
+}
+
 impl PartialEq for PacketContent {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -215,33 +641,84 @@ impl PartialEq for PacketContent {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct LiteralValue {
-    value: u64,
-    len: usize,
+    // Hex digits, most significant first, including any leading zeroes the
+    // transmission had. There may be more than fit in a u64.
+    nibbles: Vec<u8>,
 }
 
 impl<T: BitReader> ReadFrom<T> for LiteralValue {
-    fn read_from(reader: &mut T) -> Result<Self> {
-        let mut value = 0u64;
-        let mut len = 0;
-
-        let mut keep_reading = reader.read_bool()?;
-        while keep_reading {
-            value = (value << 4) + (reader.read_bits(4)? as u64);
-            len += 5;
-            keep_reading = reader.read_bool()?;
+    fn read_from(reader: &mut T) -> Result<Self, DecodeError> {
+        let mut nibbles = Vec::new();
+        loop {
+            let group = read_field(reader, 5, PacketField::LiteralGroup)?;
+            let keep_reading = group & 0b10000 != 0;
+            nibbles.push((group & 0xF) as u8);
+            if !keep_reading {
+                return Ok(LiteralValue { nibbles });
+            }
         }
-        value = (value << 4) + (reader.read_bits(4)? as u64);
-        len += 5;
+    }
+}
 
-        Ok(LiteralValue { value, len })
+impl<T: BitWriter> WriteTo<T> for LiteralValue {
+    fn write_to(&self, writer: &mut T) -> Result<()> {
+        for (i, nibble) in self.nibbles.iter().enumerate() {
+            writer.write_bool(i + 1 < self.nibbles.len())?;
+            writer.write_bits(*nibble as u64, 4)?;
+        }
+        Ok(())
     }
 }
 
 impl LiteralValue {
+    /// The shortest encoding of `value`.
+    pub fn new(value: u64) -> LiteralValue {
+        let bits = (u64::BITS - value.leading_zeros()).max(1);
+        let nibbles = (0..bits.div_ceil(4))
+            .rev()
+            .map(|i| ((value >> (i * 4)) & 0xF) as u8)
+            .collect();
+        LiteralValue { nibbles }
+    }
+
     fn len(&self) -> usize {
-        self.len
+        self.nibbles.len() * 5
+    }
+
+    /// The value, if it fits in a u64.
+    pub fn value(&self) -> Option<u64> {
+        let leading_zeroes = self
+            .nibbles
+            .iter()
+            .take_while(|nibble| **nibble == 0)
+            .count();
+        if self.nibbles.len() - leading_zeroes > 16 {
+            return None;
+        }
+        Some(
+            self.nibbles
+                .iter()
+                .fold(0, |value, nibble| (value << 4) | *nibble as u64),
+        )
+    }
+}
+
+impl Display for LiteralValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(value) = self.value() {
+            return write!(f, "{}", value);
+        }
+        #[cfg(feature = "big-integers")]
+        return write!(f, "{}", BigUint::from_nibbles(&self.nibbles));
+        #[cfg(not(feature = "big-integers"))]
+        {
+            write!(f, "0x")?;
+            self.nibbles
+                .iter()
+                .try_for_each(|nibble| write!(f, "{:X}", nibble))
+        }
     }
 }
 
@@ -249,10 +726,19 @@ impl LiteralValue {
 pub struct SubPacketLengthInBits(u16);
 
 impl<T: BitReader> ReadFrom<T> for SubPacketLengthInBits {
-    fn read_from(reader: &mut T) -> Result<Self> {
-        Ok(SubPacketLengthInBits(
-            reader.read_bits(SubPacketLengthInBits::len())?,
-        ))
+    fn read_from(reader: &mut T) -> Result<Self, DecodeError> {
+        let length = read_field(
+            reader,
+            SubPacketLengthInBits::len(),
+            PacketField::SubPacketLength,
+        )?;
+        Ok(SubPacketLengthInBits(length as u16))
+    }
+}
+
+impl<T: BitWriter> WriteTo<T> for SubPacketLengthInBits {
+    fn write_to(&self, writer: &mut T) -> Result<()> {
+        writer.write_bits(self.0 as u64, SubPacketLengthInBits::len())
     }
 }
 
@@ -266,8 +752,15 @@ impl SubPacketLengthInBits {
 pub struct NumSubPackets(u16);
 
 impl<T: BitReader> ReadFrom<T> for NumSubPackets {
-    fn read_from(reader: &mut T) -> Result<Self> {
-        Ok(NumSubPackets(reader.read_bits(NumSubPackets::len())?))
+    fn read_from(reader: &mut T) -> Result<Self, DecodeError> {
+        let count = read_field(reader, NumSubPackets::len(), PacketField::SubPacketCount)?;
+        Ok(NumSubPackets(count as u16))
+    }
+}
+
+impl<T: BitWriter> WriteTo<T> for NumSubPackets {
+    fn write_to(&self, writer: &mut T) -> Result<()> {
+        writer.write_bits(self.0 as u64, NumSubPackets::len())
     }
 }
 
@@ -277,22 +770,31 @@ impl NumSubPackets {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LengthType {
     LengthInBits,
     LengthInPackets,
 }
 
 impl<T: BitReader> ReadFrom<T> for LengthType {
-    fn read_from(reader: &mut T) -> Result<Self> {
-        match reader.read_bits(LengthType::len())? {
+    fn read_from(reader: &mut T) -> Result<Self, DecodeError> {
+        match read_field(reader, LengthType::len(), PacketField::LengthType)? {
             0 => Ok(LengthType::LengthInBits),
-            1 => Ok(LengthType::LengthInPackets),
-            _ => unreachable!("Invalid length type"),
+            _ => Ok(LengthType::LengthInPackets),
         }
     }
 }
 
+impl<T: BitWriter> WriteTo<T> for LengthType {
+    fn write_to(&self, writer: &mut T) -> Result<()> {
+        let id = match self {
+            LengthType::LengthInBits => 0,
+            LengthType::LengthInPackets => 1,
+        };
+        writer.write_bits(id, LengthType::len())
+    }
+}
+
 impl LengthType {
     fn len() -> usize {
         1
@@ -302,12 +804,12 @@ impl LengthType {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::hexreader::HexReader;
+    use crate::hexreader::{HexReader, StreamReader};
     use anyhow::Result;
 
     #[test]
     fn parse_literal() -> Result<()> {
-        let mut reader = HexReader::new("D2FE28");
+        let mut reader = HexReader::new("D2FE28")?;
         let packet = Packet::read_from(&mut reader)?;
 
         assert_eq!(
@@ -317,10 +819,7 @@ mod tests {
                     version: Version(0b110),
                     packet_type: PacketType::Literal
                 },
-                content: PacketContent::Literal(LiteralValue {
-                    value: 2021,
-                    len: 15
-                })
+                content: PacketContent::Literal(LiteralValue::new(2021))
             }
         );
         assert_eq!(packet.len(), 21);
@@ -330,7 +829,7 @@ mod tests {
 
     #[test]
     fn parse_bit_wise_subpackets() -> Result<()> {
-        let mut reader = HexReader::new("38006F45291200");
+        let mut reader = HexReader::new("38006F45291200")?;
         let packet = Packet::read_from(&mut reader)?;
 
         assert_eq!(
@@ -346,14 +845,14 @@ mod tests {
                             version: Version(0b110),
                             packet_type: PacketType::Literal,
                         },
-                        content: PacketContent::Literal(LiteralValue { value: 10, len: 5 }),
+                        content: PacketContent::Literal(LiteralValue::new(10)),
                     },
                     Packet {
                         header: PacketHeader {
                             version: Version(0b010),
                             packet_type: PacketType::Literal,
                         },
-                        content: PacketContent::Literal(LiteralValue { value: 20, len: 10 }),
+                        content: PacketContent::Literal(LiteralValue::new(20)),
                     }
                 ])
             }
@@ -365,7 +864,7 @@ mod tests {
 
     #[test]
     fn parse_number_wise_subpackets() -> Result<()> {
-        let mut reader = HexReader::new("EE00D40C823060");
+        let mut reader = HexReader::new("EE00D40C823060")?;
         let packet = Packet::read_from(&mut reader)?;
 
         assert_eq!(
@@ -381,21 +880,21 @@ mod tests {
                             version: Version(0b010),
                             packet_type: PacketType::Literal,
                         },
-                        content: PacketContent::Literal(LiteralValue { value: 1, len: 5 }),
+                        content: PacketContent::Literal(LiteralValue::new(1)),
                     },
                     Packet {
                         header: PacketHeader {
                             version: Version(0b100),
                             packet_type: PacketType::Literal,
                         },
-                        content: PacketContent::Literal(LiteralValue { value: 2, len: 5 }),
+                        content: PacketContent::Literal(LiteralValue::new(2)),
                     },
                     Packet {
                         header: PacketHeader {
                             version: Version(0b001),
                             packet_type: PacketType::Literal,
                         },
-                        content: PacketContent::Literal(LiteralValue { value: 3, len: 5 }),
+                        content: PacketContent::Literal(LiteralValue::new(3)),
                     }
                 ])
             }
@@ -407,7 +906,7 @@ mod tests {
 
     #[test]
     fn test_example_one() -> Result<()> {
-        let mut reader = HexReader::new("8A004A801A8002F478");
+        let mut reader = HexReader::new("8A004A801A8002F478")?;
         let packet = Packet::read_from(&mut reader)?;
         assert_eq!(
             packet,
@@ -431,7 +930,7 @@ mod tests {
                                 version: Version(6),
                                 packet_type: PacketType::Literal
                             },
-                            content: PacketContent::Literal(LiteralValue { value: 15, len: 5 })
+                            content: PacketContent::Literal(LiteralValue::new(15))
                         }])
                     }])
                 }])
@@ -442,7 +941,7 @@ mod tests {
 
     #[test]
     fn test_example_two() -> Result<()> {
-        let mut reader = HexReader::new("620080001611562C8802118E34");
+        let mut reader = HexReader::new("620080001611562C8802118E34")?;
         let packet = Packet::read_from(&mut reader)?;
         assert_eq!(
             packet,
@@ -463,14 +962,14 @@ mod tests {
                                     version: Version(0),
                                     packet_type: PacketType::Literal
                                 },
-                                content: PacketContent::Literal(LiteralValue { value: 10, len: 5 })
+                                content: PacketContent::Literal(LiteralValue::new(10))
                             },
                             Packet {
                                 header: PacketHeader {
                                     version: Version(5),
                                     packet_type: PacketType::Literal
                                 },
-                                content: PacketContent::Literal(LiteralValue { value: 11, len: 5 })
+                                content: PacketContent::Literal(LiteralValue::new(11))
                             }
                         ])
                     },
@@ -485,14 +984,14 @@ mod tests {
                                     version: Version(0),
                                     packet_type: PacketType::Literal
                                 },
-                                content: PacketContent::Literal(LiteralValue { value: 12, len: 5 })
+                                content: PacketContent::Literal(LiteralValue::new(12))
                             },
                             Packet {
                                 header: PacketHeader {
                                     version: Version(3),
                                     packet_type: PacketType::Literal
                                 },
-                                content: PacketContent::Literal(LiteralValue { value: 13, len: 5 })
+                                content: PacketContent::Literal(LiteralValue::new(13))
                             }
                         ])
                     }
@@ -504,7 +1003,7 @@ mod tests {
 
     #[test]
     fn test_example_three() -> Result<()> {
-        let mut reader = HexReader::new("C0015000016115A2E0802F182340");
+        let mut reader = HexReader::new("C0015000016115A2E0802F182340")?;
         let packet = Packet::read_from(&mut reader)?;
         assert_eq!(
             packet,
@@ -525,14 +1024,14 @@ mod tests {
                                     version: Version(0),
                                     packet_type: PacketType::Literal
                                 },
-                                content: PacketContent::Literal(LiteralValue { value: 10, len: 5 })
+                                content: PacketContent::Literal(LiteralValue::new(10))
                             },
                             Packet {
                                 header: PacketHeader {
                                     version: Version(6),
                                     packet_type: PacketType::Literal
                                 },
-                                content: PacketContent::Literal(LiteralValue { value: 11, len: 5 })
+                                content: PacketContent::Literal(LiteralValue::new(11))
                             }
                         ])
                     },
@@ -547,14 +1046,14 @@ mod tests {
                                     version: Version(7),
                                     packet_type: PacketType::Literal
                                 },
-                                content: PacketContent::Literal(LiteralValue { value: 12, len: 5 })
+                                content: PacketContent::Literal(LiteralValue::new(12))
                             },
                             Packet {
                                 header: PacketHeader {
                                     version: Version(0),
                                     packet_type: PacketType::Literal
                                 },
-                                content: PacketContent::Literal(LiteralValue { value: 13, len: 5 })
+                                content: PacketContent::Literal(LiteralValue::new(13))
                             }
                         ])
                     }
@@ -578,11 +1077,299 @@ mod tests {
         ];
 
         for (input, expected) in test_cases {
-            let mut reader = HexReader::new(input);
+            let mut reader = HexReader::new(input)?;
             let packet = Packet::read_from(&mut reader)?;
-            assert_eq!(packet.evaluate(), expected);
+            assert_eq!(packet.evaluate()?, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn evaluation_errors() -> Result<()> {
+        let packet = Packet::operator(
+            0,
+            PacketType::LessThan,
+            LengthType::LengthInPackets,
+            vec![Packet::literal(0, 1)],
+        );
+        assert_eq!(
+            packet.evaluate(),
+            Err(EvaluationError::Arity {
+                packet_type: PacketType::LessThan,
+                expected: 2,
+                found: 1
+            })
+        );
+
+        let packet = Packet::operator(0, PacketType::Minimum, LengthType::LengthInBits, vec![]);
+        assert_eq!(
+            packet.evaluate(),
+            Err(EvaluationError::Empty(PacketType::Minimum))
+        );
+
+        let packet = Packet::operator(
+            0,
+            PacketType::Product,
+            LengthType::LengthInPackets,
+            vec![Packet::literal(0, u64::MAX), Packet::literal(0, 2)],
+        );
+        assert_eq!(
+            packet.evaluate(),
+            Err(EvaluationError::Overflow(PacketType::Product))
+        );
+        assert_eq!(
+            packet.evaluate().unwrap_err().to_string(),
+            "Product overflowed"
+        );
+
+        let packet = Packet::read_from(&mut HexReader::from_binary(&wide_literal())?)?;
+        assert_eq!(
+            packet.evaluate(),
+            Err(EvaluationError::Overflow(PacketType::Literal))
+        );
+        Ok(())
+    }
+
+    // A literal of 17 hex digits, all 1.
+    fn wide_literal() -> String {
+        format!("000100{}00001", "10001".repeat(16))
+    }
+
+    #[test]
+    fn wide_literal_round_trip() -> Result<()> {
+        let packet = Packet::read_from(&mut HexReader::from_binary(&wide_literal())?)?;
+        let decoded = Packet::read_from(&mut HexReader::new(&packet.encode()?)?)?;
+        assert_eq!(decoded, packet);
+        #[cfg(not(feature = "big-integers"))]
+        assert_eq!(packet.infix(), "0x11111111111111111");
+        #[cfg(feature = "big-integers")]
+        assert_eq!(packet.infix(), "19676527011956855057");
+        Ok(())
+    }
+
+    #[cfg(feature = "big-integers")]
+    #[test]
+    fn evaluate_big() -> Result<()> {
+        use crate::bigint::BigUint;
+
+        let wide = Packet::read_from(&mut HexReader::from_binary(&wide_literal())?)?;
+        let packet = Packet::operator(
+            0,
+            PacketType::Product,
+            LengthType::LengthInPackets,
+            vec![wide, Packet::literal(0, u64::MAX), Packet::literal(0, 2)],
+        );
+        assert_eq!(
+            packet.evaluate_as::<BigUint>()?.to_string(),
+            "725935716098002055346719875954030534110"
+        );
+
+        let packet = crate::expression::parse("18446744073709551615 + 1 > 18446744073709551615")?;
+        assert_eq!(packet.evaluate_as::<BigUint>()?, BigUint::from(1));
+        Ok(())
+    }
+
+    #[test]
+    fn encode() -> Result<()> {
+        assert_eq!(Packet::literal(6, 2021).encode()?, "D2FE28");
+
+        let packet = Packet::operator(
+            1,
+            PacketType::LessThan,
+            LengthType::LengthInBits,
+            vec![Packet::literal(6, 10), Packet::literal(2, 20)],
+        );
+        assert_eq!(packet.encode()?, "38006F4529120");
+
+        let packet = Packet::operator(
+            7,
+            PacketType::Maximum,
+            LengthType::LengthInPackets,
+            vec![
+                Packet::literal(2, 1),
+                Packet::literal(4, 2),
+                Packet::literal(1, 3),
+            ],
+        );
+        assert_eq!(packet.encode()?, "EE00D40C82306");
+        Ok(())
+    }
+
+    #[test]
+    fn encode_errors() {
+        assert!(Packet::literal(8, 1).encode().is_err());
+
+        let mut packet = Packet::literal(0, 1);
+        packet.header.packet_type = PacketType::Sum;
+        assert!(packet.encode().is_err());
+
+        let many = (0..2048).map(|i| Packet::literal(0, i)).collect();
+        let packet = Packet::operator(0, PacketType::Sum, LengthType::LengthInPackets, many);
+        assert!(packet.encode().is_err());
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let transmissions = [
+            "D2FE28",
+            "38006F45291200",
+            "EE00D40C823060",
+            "8A004A801A8002F478",
+            "620080001611562C8802118E34",
+            "C0015000016115A2E0802F182340",
+            "A0016C880162017C3686B18A3D4780",
+            "9C0141080250320F1802104A08",
+        ];
+        for transmission in transmissions {
+            let packet = Packet::read_from(&mut HexReader::new(transmission)?)?;
+            let encoded = packet.encode()?;
+            assert!(transmission.starts_with(&encoded));
+            assert_eq!(Packet::read_from(&mut HexReader::new(&encoded)?)?, packet);
         }
 
+        let packet = Packet::operator(
+            5,
+            PacketType::Product,
+            LengthType::LengthInBits,
+            vec![
+                Packet::literal(0, u64::MAX),
+                Packet::operator(
+                    3,
+                    PacketType::EqualTo,
+                    LengthType::LengthInPackets,
+                    vec![Packet::literal(1, 0), Packet::literal(7, 15)],
+                ),
+            ],
+        );
+        let decoded = Packet::read_from(&mut HexReader::new(&packet.encode()?)?)?;
+        assert_eq!(decoded, packet);
+        assert_eq!(decoded.evaluate()?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn render() -> Result<()> {
+        let packet = Packet::operator(
+            0,
+            PacketType::Product,
+            LengthType::LengthInPackets,
+            vec![
+                Packet::operator(
+                    0,
+                    PacketType::Sum,
+                    LengthType::LengthInBits,
+                    vec![Packet::literal(0, 3), Packet::literal(0, 5)],
+                ),
+                Packet::operator(
+                    0,
+                    PacketType::Minimum,
+                    LengthType::LengthInPackets,
+                    vec![Packet::literal(0, 7), Packet::literal(0, 9)],
+                ),
+            ],
+        );
+        assert_eq!(packet.infix(), "(3 + 5) * min(7, 9)");
+        assert_eq!(packet.sexpr(), "(* (+ 3 5) (min 7 9))");
+
+        let packet = Packet::read_from(&mut HexReader::new("9C0141080250320F1802104A08")?)?;
+        assert_eq!(packet.infix(), "(1 + 3) == (2 * 2)");
+        assert_eq!(packet.sexpr(), "(== (+ 1 3) (* 2 2))");
+
+        let packet = Packet::read_from(&mut HexReader::new("C200B40A82")?)?;
+        assert_eq!(packet.infix(), "1 + 2");
+
+        let packet = Packet::operator(
+            0,
+            PacketType::Sum,
+            LengthType::LengthInPackets,
+            vec![Packet::literal(0, 1)],
+        );
+        assert_eq!(packet.infix(), "sum(1)");
+        Ok(())
+    }
+
+    #[test]
+    fn tree() -> Result<()> {
+        let packet = Packet::read_from(&mut HexReader::new("38006F45291200")?)?;
+        assert_eq!(
+            packet.tree(),
+            "@0 v1 LessThan length 27 (49 bits)\n  \
+             @22 v6 Literal 10 (11 bits)\n  \
+             @33 v2 Literal 20 (16 bits)\n"
+        );
+
+        let packet = Packet::read_from(&mut HexReader::new("8A004A801A8002F478")?)?;
+        assert_eq!(
+            packet.tree(),
+            "@0 v4 Minimum count 1 (69 bits)\n  \
+             @18 v1 Minimum count 1 (51 bits)\n    \
+             @36 v5 Minimum length 11 (33 bits)\n      \
+             @58 v6 Literal 15 (11 bits)\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_errors() -> Result<()> {
+        let decode = |hex| Packet::decode(&mut HexReader::new(hex).unwrap());
+
+        // The literal's last group is cut off.
+        assert_eq!(
+            decode("D2FE"),
+            Err(DecodeError {
+                offset: 16,
+                field: PacketField::LiteralGroup,
+                reason: DecodeReason::Truncated
+            })
+        );
+        assert_eq!(
+            decode("3").unwrap_err().to_string(),
+            "PacketType at bit 3: truncated"
+        );
+
+        // 38006F45291200 with the sub-packet length cut from 27 bits to 26.
+        let error = decode("38006945291200").unwrap_err();
+        assert_eq!(
+            error,
+            DecodeError {
+                offset: 22,
+                field: PacketField::SubPackets,
+                reason: DecodeReason::LengthOverrun {
+                    expected: 26,
+                    found: 27
+                }
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "SubPackets at bit 22: 27 bits long, expected 26"
+        );
+
+        assert_eq!(
+            decode("D2FE2C"),
+            Err(DecodeError {
+                offset: 21,
+                field: PacketField::Padding,
+                reason: DecodeReason::NonZeroPadding
+            })
+        );
+        assert_eq!(decode("D2FE2800000001").unwrap_err().offset, 55);
+        decode("D2FE2800000000")?;
+        Ok(())
+    }
+
+    #[test]
+    fn decode_stream() -> Result<()> {
+        let bytes: &[u8] = &[0xD2, 0xFE, 0x28, 0x00];
+        assert!(Packet::decode(&mut StreamReader::new(bytes)).is_ok());
+        let bytes: &[u8] = &[0xD2, 0xFE, 0x28, 0x02];
+        assert_eq!(
+            Packet::decode(&mut StreamReader::new(bytes))
+                .unwrap_err()
+                .offset,
+            30
+        );
         Ok(())
     }
 }
@@ -0,0 +1,54 @@
+use crate::{
+    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
+    day14, day15, day16, day17, day18, day19, day20, day21, solution::Runner,
+};
+
+// Day 22 isn't solved yet, so it has no entry and `verify` and `bench` skip
+// it. The Readme says so too; keep both in step when it's added.
+static SOLUTIONS: [&dyn Runner; 21] = [
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+    &day09::Day09,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20,
+    &day21::Day21,
+];
+
+pub fn all() -> impl Iterator<Item = &'static dyn Runner> {
+    SOLUTIONS.iter().copied()
+}
+
+pub fn get(day: u32) -> Option<&'static dyn Runner> {
+    all().find(|solution| solution.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn days_are_unique_and_ordered() {
+        let days: Vec<u32> = super::all().map(|solution| solution.day()).collect();
+        assert_eq!(days, (1..=21).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn get() {
+        assert_eq!(super::get(16).map(|solution| solution.day()), Some(16));
+        assert!(super::get(22).is_none());
+        assert!(super::get(0).is_none());
+    }
+}
@@ -0,0 +1,539 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use crate::matrix::{Matrix, Point};
+
+// Searches take the graph as a neighbour function, so anything from a grid to
+// an adjacency list can be searched without implementing a trait.
+
+/// Shortest path by number of steps, from `start` to the first node that
+/// satisfies `is_goal`. Both ends are included.
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents = HashMap::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut q = VecDeque::from([start]);
+    while let Some(node) = q.pop_front() {
+        if is_goal(&node) {
+            return Some(reconstruct_path(&parents, node));
+        }
+        for neighbour in neighbours(&node) {
+            if seen.insert(neighbour.clone()) {
+                parents.insert(neighbour.clone(), node.clone());
+                q.push_back(neighbour);
+            }
+        }
+    }
+    None
+}
+
+/// Some path from `start` to a node that satisfies `is_goal`, not
+/// necessarily the shortest.
+pub fn dfs<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        if !visited.insert(node.clone()) {
+            continue;
+        }
+        if is_goal(&node) {
+            return Some(reconstruct_path(&parents, node));
+        }
+        for neighbour in neighbours(&node) {
+            if !visited.contains(&neighbour) {
+                // The last node to push a neighbour is the one it gets popped after.
+                parents.insert(neighbour.clone(), node.clone());
+                stack.push(neighbour);
+            }
+        }
+    }
+    None
+}
+
+/// Cheapest path to a node that satisfies `is_goal`, and its cost.
+/// `neighbours` yields each neighbour with the cost of stepping to it.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbours, |_| C::default(), is_goal)
+}
+
+/// Dijkstra guided by `heuristic`, an estimate of the remaining cost from a
+/// node. The path is only guaranteed cheapest if the estimate never exceeds
+/// the real cost.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    // Nodes live in `nodes` so the heap only has to order costs and indices.
+    let mut nodes = vec![start.clone()];
+    let mut costs = HashMap::from([(start.clone(), C::default())]);
+    let mut parents = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let node = nodes[index].clone();
+        if !visited.insert(node.clone()) {
+            continue;
+        }
+        if is_goal(&node) {
+            return Some((reconstruct_path(&parents, node), cost));
+        }
+        for (neighbour, step) in neighbours(&node) {
+            let new_cost = cost + step;
+            // Already a better route to this point
+            if visited.contains(&neighbour) || costs.get(&neighbour).is_some_and(|c| *c <= new_cost)
+            {
+                continue;
+            }
+            costs.insert(neighbour.clone(), new_cost);
+            parents.insert(neighbour.clone(), node.clone());
+            heap.push(Reverse((
+                new_cost + heuristic(&neighbour),
+                new_cost,
+                nodes.len(),
+            )));
+            nodes.push(neighbour);
+        }
+    }
+    None
+}
+
+/// Every node reachable from `start`, including itself, nearest first.
+pub fn flood_fill<N, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> Vec<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::from([start.clone()]);
+    let mut result = vec![start];
+    let mut next = 0;
+    while let Some(node) = result.get(next).cloned() {
+        next += 1;
+        for neighbour in neighbours(&node) {
+            if seen.insert(neighbour.clone()) {
+                result.push(neighbour);
+            }
+        }
+    }
+    result
+}
+
+/// Splits `nodes` into groups that can reach each other. `neighbours` must be
+/// symmetric, and only yield nodes that are in `nodes`.
+pub fn connected_components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> Vec<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::new();
+    let mut components = Vec::new();
+    for node in nodes {
+        if seen.contains(&node) {
+            continue;
+        }
+        let component = flood_fill(node, &mut neighbours);
+        seen.extend(component.iter().cloned());
+        components.push(component);
+    }
+    components
+}
+
+/// Every path from `start` that ends at a node satisfying `is_goal`.
+/// `extend` is given the path so far, so it can refuse to revisit nodes.
+pub fn all_paths<N, I>(
+    start: N,
+    mut extend: impl FnMut(&[N]) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Vec<Vec<N>>
+where
+    N: Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut result = Vec::new();
+    let mut stack = vec![vec![start]];
+    while let Some(path) = stack.pop() {
+        if is_goal(path.last().unwrap()) {
+            result.push(path);
+            continue;
+        }
+        for next in extend(&path) {
+            let mut new_path = path.clone();
+            new_path.push(next);
+            stack.push(new_path);
+        }
+    }
+    result
+}
+
+/// Follows `parents` back from `end`, returning the path in order.
+pub fn reconstruct_path<N: Eq + Hash + Clone>(parents: &HashMap<N, N>, end: N) -> Vec<N> {
+    let mut path = vec![end];
+    while let Some(parent) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// A path through a `Matrix`, where stepping onto a cell costs its value.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Route {
+    /// Includes both ends.
+    pub path: Vec<Point>,
+    pub cost: u32,
+    /// How many cells were taken off the queue, in either direction. The end
+    /// counts if it was taken off the queue, whether or not its neighbours were
+    /// then checked.
+    pub expanded: usize,
+}
+
+// These keep their state in flat vectors indexed like the matrix storage,
+// which is much faster than hashing points on big grids.
+impl Matrix {
+    pub fn dijkstra_route(&self, start: Point, end: Point) -> Option<Route> {
+        self.flat_astar(start, end, |_| 0)
+    }
+
+    /// A* with the Manhattan distance to `end`, scaled by the cheapest cell so
+    /// it never overestimates.
+    pub fn astar_route(&self, start: Point, end: Point) -> Option<Route> {
+        let cheapest = self.values().copied().min().unwrap_or(0);
+        self.flat_astar(start, end, |point| {
+            (point.x.abs_diff(end.x) + point.y.abs_diff(end.y)) as u32 * cheapest
+        })
+    }
+
+    /// Dijkstra from both ends at once, stopping once the two searches can't
+    /// find anything cheaper than the best meeting point so far.
+    pub fn bidirectional_route(&self, start: Point, end: Point) -> Option<Route> {
+        let (start, end) = (self.flat_index(&start)?, self.flat_index(&end)?);
+        // Searching backwards, stepping from a cell to its neighbour costs the
+        // cell's value, since that's what the forward step onto it would cost.
+        let mut forward = FlatSearch::new(self.len(), start);
+        let mut backward = FlatSearch::new(self.len(), end);
+        let mut best = if start == end { Some((0, start)) } else { None };
+        let mut expanded = 0;
+
+        while let (Some(forward_top), Some(backward_top)) = (forward.peek(), backward.peek()) {
+            if best.is_some_and(|(cost, _)| forward_top + backward_top >= cost) {
+                break;
+            }
+            let is_forward = forward_top <= backward_top;
+            let (search, other) = if is_forward {
+                (&mut forward, &backward)
+            } else {
+                (&mut backward, &forward)
+            };
+            let index = match search.pop() {
+                Some(index) => index,
+                None => continue,
+            };
+            expanded += 1;
+            for neighbour in self.neighbours(self.flat_point(index)) {
+                let neighbour = self.flat_index(&neighbour).unwrap();
+                let step = if is_forward {
+                    self.storage_value(neighbour)
+                } else {
+                    self.storage_value(index)
+                };
+                search.relax(index, neighbour, step, 0);
+                if other.cost[neighbour] != u32::MAX && search.cost[neighbour] != u32::MAX {
+                    let total = search.cost[neighbour] + other.cost[neighbour];
+                    if best.is_none_or(|(cost, _)| total < cost) {
+                        best = Some((total, neighbour));
+                    }
+                }
+            }
+        }
+
+        let (cost, meeting) = best?;
+        let mut path = forward.path_to(meeting);
+        let mut rest = backward.path_to(meeting);
+        rest.pop();
+        path.extend(rest.into_iter().rev());
+        Some(Route {
+            path: path.into_iter().map(|i| self.flat_point(i)).collect(),
+            cost,
+            expanded,
+        })
+    }
+
+    fn flat_astar(
+        &self,
+        start: Point,
+        end: Point,
+        heuristic: impl Fn(&Point) -> u32,
+    ) -> Option<Route> {
+        let (start, end) = (self.flat_index(&start)?, self.flat_index(&end)?);
+        let mut search = FlatSearch::new(self.len(), start);
+        let mut expanded = 0;
+        while let Some(index) = search.pop() {
+            expanded += 1;
+            if index == end {
+                return Some(Route {
+                    path: search
+                        .path_to(end)
+                        .into_iter()
+                        .map(|i| self.flat_point(i))
+                        .collect(),
+                    cost: search.cost[end],
+                    expanded,
+                });
+            }
+            for neighbour in self.neighbours(self.flat_point(index)) {
+                let estimate = heuristic(&neighbour);
+                let neighbour = self.flat_index(&neighbour).unwrap();
+                search.relax(index, neighbour, self.storage_value(neighbour), estimate);
+            }
+        }
+        None
+    }
+
+    fn flat_index(&self, point: &Point) -> Option<usize> {
+        (point.x < self.max_x && point.y < self.max_y).then_some(point.y * self.max_x + point.x)
+    }
+
+    fn flat_point(&self, index: usize) -> Point {
+        Point {
+            x: index % self.max_x,
+            y: index / self.max_x,
+        }
+    }
+
+    fn storage_value(&self, index: usize) -> u32 {
+        *self.value(&self.flat_point(index)).unwrap()
+    }
+}
+
+// One direction of a search over storage indices. `u32::MAX` is unreached.
+struct FlatSearch {
+    cost: Vec<u32>,
+    parent: Vec<usize>,
+    closed: Vec<bool>,
+    heap: BinaryHeap<Reverse<(u32, usize)>>,
+}
+
+impl FlatSearch {
+    fn new(len: usize, start: usize) -> FlatSearch {
+        let mut cost = vec![u32::MAX; len];
+        cost[start] = 0;
+        FlatSearch {
+            cost,
+            parent: vec![usize::MAX; len],
+            closed: vec![false; len],
+            heap: BinaryHeap::from([Reverse((0, start))]),
+        }
+    }
+
+    // The lowest priority still queued, skipping entries for closed cells.
+    fn peek(&mut self) -> Option<u32> {
+        while let Some(Reverse((priority, index))) = self.heap.peek() {
+            if !self.closed[*index] {
+                return Some(*priority);
+            }
+            self.heap.pop();
+        }
+        None
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        while let Some(Reverse((_, index))) = self.heap.pop() {
+            if !self.closed[index] {
+                self.closed[index] = true;
+                return Some(index);
+            }
+        }
+        None
+    }
+
+    fn relax(&mut self, from: usize, to: usize, step: u32, estimate: u32) {
+        let new_cost = self.cost[from] + step;
+        if self.closed[to] || new_cost >= self.cost[to] {
+            return;
+        }
+        self.cost[to] = new_cost;
+        self.parent[to] = from;
+        self.heap.push(Reverse((new_cost + estimate, to)));
+    }
+
+    fn path_to(&self, end: usize) -> Vec<usize> {
+        let mut path = vec![end];
+        while self.parent[*path.last().unwrap()] != usize::MAX {
+            path.push(self.parent[*path.last().unwrap()]);
+        }
+        path.reverse();
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::matrix::{Matrix, Point};
+
+    // 0 - 1 - 2 - 3
+    //  \_____4_____/   with 0 - 4 and 4 - 3 costing 5 each, other edges 1.
+    fn edges(node: &u32) -> Vec<(u32, u32)> {
+        match node {
+            0 => vec![(1, 1), (4, 5)],
+            1 => vec![(0, 1), (2, 1)],
+            2 => vec![(1, 1), (3, 1)],
+            3 => vec![(2, 1), (4, 5)],
+            4 => vec![(0, 5), (3, 5)],
+            _ => vec![],
+        }
+    }
+
+    fn neighbours(node: &u32) -> Vec<u32> {
+        edges(node).into_iter().map(|(n, _)| n).collect()
+    }
+
+    #[test]
+    fn bfs_and_dfs() {
+        assert_eq!(super::bfs(0, neighbours, |n| *n == 3), Some(vec![0, 4, 3]));
+        assert_eq!(super::bfs(0, neighbours, |n| *n == 9), None);
+
+        let path = super::dfs(0, neighbours, |n| *n == 3).unwrap();
+        assert_eq!((path[0], path[path.len() - 1]), (0, 3));
+        assert!(path.windows(2).all(|w| neighbours(&w[0]).contains(&w[1])));
+    }
+
+    #[test]
+    fn weighted() {
+        assert_eq!(
+            super::dijkstra(0, edges, |n| *n == 3),
+            Some((vec![0, 1, 2, 3], 3))
+        );
+        let distance_to_3 = |n: &u32| 3u32.abs_diff(*n).min(1);
+        assert_eq!(
+            super::astar(0, edges, distance_to_3, |n| *n == 3),
+            Some((vec![0, 1, 2, 3], 3))
+        );
+        assert_eq!(super::dijkstra(5, edges, |n| *n == 3), None);
+    }
+
+    #[test]
+    fn components() {
+        let mut filled = super::flood_fill(1, neighbours);
+        filled.sort_unstable();
+        assert_eq!(filled, [0, 1, 2, 3, 4]);
+
+        // Even numbers up to 8 are linked to the next even number, odd ones are alone.
+        let link = |n: &u32| {
+            [n.checked_sub(2), Some(n + 2)]
+                .into_iter()
+                .flatten()
+                .filter(move |m| n.is_multiple_of(2) && *m <= 8)
+                .collect::<Vec<u32>>()
+        };
+        let components = super::connected_components(0..9, link);
+        assert_eq!(components.len(), 5);
+        assert_eq!(components[0].len(), 5);
+    }
+
+    #[test]
+    fn all_paths() {
+        let paths = super::all_paths(
+            0,
+            |path: &[u32]| {
+                neighbours(path.last().unwrap())
+                    .into_iter()
+                    .filter(|n| !path.contains(n))
+                    .collect::<Vec<u32>>()
+            },
+            |n| *n == 3,
+        );
+        assert_eq!(paths.len(), 2);
+    }
+
+    #[test]
+    fn reconstruct_path() {
+        let parents = HashMap::from([(3, 2), (2, 1)]);
+        assert_eq!(super::reconstruct_path(&parents, 3), [1, 2, 3]);
+        assert_eq!(super::reconstruct_path(&parents, 7), [7]);
+    }
+
+    #[test]
+    fn matrix_routes() -> anyhow::Result<()> {
+        let input = crate::input::read("day15-test")?;
+        let matrix = Matrix::new(&input)?.construct_extended_matrix();
+        let start = Point { x: 0, y: 0 };
+        let end = Point {
+            x: matrix.max_x - 1,
+            y: matrix.max_y - 1,
+        };
+
+        let dijkstra = matrix.dijkstra_route(start, end).unwrap();
+        let astar = matrix.astar_route(start, end).unwrap();
+        let bidirectional = matrix.bidirectional_route(start, end).unwrap();
+        for route in [&dijkstra, &astar, &bidirectional] {
+            assert_eq!(route.cost, 315);
+            assert_eq!(route.path.first(), Some(&start));
+            assert_eq!(route.path.last(), Some(&end));
+            let risk: u32 = route.path[1..]
+                .iter()
+                .map(|p| matrix.value(p).unwrap())
+                .sum();
+            assert_eq!(risk, 315);
+        }
+        assert!(astar.expanded <= dijkstra.expanded);
+        assert!(bidirectional.expanded <= dijkstra.expanded);
+
+        let same = matrix.bidirectional_route(start, start).unwrap();
+        assert_eq!((same.path, same.cost), (vec![start], 0));
+        assert_eq!(matrix.astar_route(start, Point { x: 999, y: 0 }), None);
+        Ok(())
+    }
+
+    #[test]
+    fn expanded_counts() -> anyhow::Result<()> {
+        let matrix = Matrix::new("11111")?;
+        let (start, end) = (Point { x: 0, y: 0 }, Point { x: 4, y: 0 });
+        // One way takes every cell off the queue, the end included.
+        assert_eq!(matrix.dijkstra_route(start, end).unwrap().expanded, 5);
+        assert_eq!(matrix.astar_route(start, end).unwrap().expanded, 5);
+        // Both ends take two cells each and meet in the middle, which neither takes.
+        assert_eq!(matrix.bidirectional_route(start, end).unwrap().expanded, 4);
+        Ok(())
+    }
+}
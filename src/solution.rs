@@ -0,0 +1,72 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+
+/// A day's puzzle. Parsing is kept separate from solving so the input can be
+/// checked once and each part gets its own copy to consume.
+pub trait Solution {
+    const DAY: u32;
+
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part_1(input: Self::Input) -> Result<Self::Answer1>;
+
+    fn part_2(input: Self::Input) -> Result<Self::Answer2>;
+}
+
+/// The answer to one part, along with how long parsing and solving took.
+pub struct Run {
+    pub answer: String,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// Object safe view of a `Solution`, so every day can live in the same registry.
+pub trait Runner: Sync {
+    fn day(&self) -> u32;
+
+    fn timed_run(&self, part: u32, input: &str) -> Result<Run>;
+
+    fn run(&self, part: u32, input: &str) -> Result<String> {
+        Ok(self.timed_run(part, input)?.answer)
+    }
+}
+
+impl<S: Solution + Sync> Runner for S {
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn timed_run(&self, part: u32, input: &str) -> Result<Run> {
+        if part != 1 && part != 2 {
+            return Err(anyhow!("Part must be 1 or 2"));
+        }
+
+        let start = Instant::now();
+        let input = S::parse(input)?;
+        let parse_time = start.elapsed();
+
+        let start = Instant::now();
+        // Stop the clock before formatting the answer.
+        let (solve_time, answer) = if part == 1 {
+            let answer = S::part_1(input)?;
+            (start.elapsed(), answer.to_string())
+        } else {
+            let answer = S::part_2(input)?;
+            (start.elapsed(), answer.to_string())
+        };
+
+        Ok(Run {
+            answer,
+            parse_time,
+            solve_time,
+        })
+    }
+}
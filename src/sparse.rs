@@ -0,0 +1,228 @@
+use std::{collections::HashMap, fmt::Display};
+
+/// A point on an unbounded grid.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// The smallest rectangle holding every cell that has been set. Both ends are inclusive.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Bounds {
+    pub min_x: i32,
+    pub max_x: i32,
+    pub min_y: i32,
+    pub max_y: i32,
+}
+
+/// A grid that stores only the cells that have been set. Every other cell,
+/// including those at negative coordinates, holds `default`.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    default: T,
+    bounds: Option<Bounds>,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+}
+
+impl Bounds {
+    pub fn contains(&self, point: &Point) -> bool {
+        (self.min_x..=self.max_x).contains(&point.x) && (self.min_y..=self.max_y).contains(&point.y)
+    }
+
+    /// Grows by `n` on every side.
+    pub fn grow(&self, n: i32) -> Bounds {
+        Bounds {
+            min_x: self.min_x - n,
+            max_x: self.max_x + n,
+            min_y: self.min_y - n,
+            max_y: self.max_y + n,
+        }
+    }
+
+    /// Every point inside, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let Bounds {
+            min_x,
+            max_x,
+            min_y,
+            max_y,
+        } = *self;
+        (min_y..=max_y).flat_map(move |y| (min_x..=max_x).map(move |x| Point { x, y }))
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max_x - self.min_x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max_y - self.min_y + 1) as usize
+    }
+
+    fn including(self, point: &Point) -> Bounds {
+        Bounds {
+            min_x: self.min_x.min(point.x),
+            max_x: self.max_x.max(point.x),
+            min_y: self.min_y.min(point.y),
+            max_y: self.max_y.max(point.y),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    pub fn get(&self, point: &Point) -> &T {
+        self.cells.get(point).unwrap_or(&self.default)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> &mut T
+    where
+        T: Clone,
+    {
+        self.track(&point);
+        self.cells
+            .entry(point)
+            .or_insert_with(|| self.default.clone())
+    }
+
+    pub fn set(&mut self, point: Point, value: T) {
+        self.track(&point);
+        self.cells.insert(point, value);
+    }
+
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    /// Changes what every unset cell holds.
+    pub fn set_default(&mut self, default: T) {
+        self.default = default;
+    }
+
+    /// `None` until a cell is set.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    /// The cells that have been set, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.values()
+    }
+
+    /// Renders the bounding box one character per cell, top row first.
+    pub fn render(&self, cell: impl Fn(&T) -> char) -> String {
+        let bounds = match self.bounds {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+        let mut result = String::with_capacity((bounds.width() + 1) * bounds.height());
+        for point in bounds.points() {
+            result.push(cell(self.get(&point)));
+            if point.x == bounds.max_x {
+                result.push('\n');
+            }
+        }
+        result
+    }
+
+    fn track(&mut self, point: &Point) {
+        self.bounds = Some(match self.bounds {
+            Some(bounds) => bounds.including(point),
+            None => Bounds {
+                min_x: point.x,
+                max_x: point.x,
+                min_y: point.y,
+                max_y: point.y,
+            },
+        });
+    }
+}
+
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(bounds) = self.bounds {
+            for point in bounds.points() {
+                write!(f, "{}", self.get(&point))?;
+                if point.x == bounds.max_x {
+                    writeln!(f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bounds, Point, SparseGrid};
+
+    #[test]
+    fn default_and_bounds() {
+        let mut grid = SparseGrid::new(0);
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(*grid.get(&Point::new(-5, 3)), 0);
+
+        grid.set(Point::new(-2, 1), 4);
+        *grid.get_mut(Point::new(3, -1)) += 2;
+        *grid.get_mut(Point::new(3, -1)) += 2;
+        assert_eq!(*grid.get(&Point::new(-2, 1)), 4);
+        assert_eq!(*grid.get(&Point::new(3, -1)), 4);
+        assert_eq!(
+            grid.bounds(),
+            Some(Bounds {
+                min_x: -2,
+                max_x: 3,
+                min_y: -1,
+                max_y: 1,
+            })
+        );
+        assert_eq!(grid.values().count(), 2);
+
+        grid.set_default(7);
+        assert_eq!(*grid.get(&Point::new(100, 100)), 7);
+    }
+
+    #[test]
+    fn render() {
+        let mut grid = SparseGrid::new(false);
+        grid.set(Point::new(-1, -1), true);
+        grid.set(Point::new(1, 0), true);
+        assert_eq!(grid.render(|c| if *c { '#' } else { '.' }), "#..\n..#\n");
+
+        let mut digits = SparseGrid::new(0);
+        digits.set(Point::new(0, 0), 1);
+        digits.set(Point::new(1, 1), 2);
+        assert_eq!(digits.to_string(), "10\n02\n");
+    }
+
+    #[test]
+    fn bounds() {
+        let bounds = Bounds {
+            min_x: 0,
+            max_x: 1,
+            min_y: 0,
+            max_y: 0,
+        };
+        assert_eq!(bounds.points().count(), 2);
+        assert_eq!(bounds.grow(1).points().count(), 12);
+        assert!(bounds.contains(&Point::new(1, 0)));
+        assert!(!bounds.contains(&Point::new(2, 0)));
+    }
+}